@@ -0,0 +1,27 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize)]
+struct Texture {
+	name: String,
+	#[serde(with = "serde_bytes")]
+	pixels: Vec<u8>,
+}
+
+/// a 8 MB byte field, moved to the visitor by [`from_data`] and cloned by [`from_data_ref`]
+fn large_blob(c: &mut Criterion) {
+	let texture = Texture { name: "background".to_string(), pixels: vec![7; 8 << 20] };
+	let data = to_data(&texture).unwrap();
+	let mut group = c.benchmark_group("deserialize a 8 MB byte field");
+	group.bench_function("from_data", |b| b.iter_batched(|| data.clone(), |mut data| from_data::<Texture>(&mut data).unwrap(), BatchSize::LargeInput));
+	group.bench_function("from_data_ref", |b| b.iter(|| from_data_ref::<Texture>(&data).unwrap()));
+	group.finish();
+}
+
+criterion_group!(benches, large_blob);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize)]
+struct Particle {
+	name: String,
+	position: (f32, f32),
+	tags: Vec<String>,
+}
+
+/// 10000 particles, moved out of the data by [`from_data`] and cloned by [`from_data_ref`]
+fn large_tree(c: &mut Criterion) {
+	let particles: Vec<Particle> = (0..10_000).map(|index| Particle {
+		name: format!("particle {}", index),
+		position: (index as f32, 0.0),
+		tags: vec!["spark".to_string(), "fading".to_string()],
+	}).collect();
+	let data = to_data(&particles).unwrap();
+	let mut group = c.benchmark_group("deserialize 10000 structs");
+	group.bench_function("from_data", |b| b.iter_batched(|| data.clone(), |mut data| from_data::<Vec<Particle>>(&mut data).unwrap(), BatchSize::LargeInput));
+	group.bench_function("from_data_ref", |b| b.iter(|| from_data_ref::<Vec<Particle>>(&data).unwrap()));
+	group.finish();
+}
+
+criterion_group!(benches, large_tree);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use nablo_data::*;
+use nablo_shape::prelude::Animation;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::hint::black_box;
+use time::Duration;
+
+#[derive(Serialize, Deserialize)]
+struct Scene {
+	values: BTreeMap<String, f32>,
+}
+
+struct Animated {
+	target: Scene,
+	map: HashMap<String, Animation>,
+}
+
+impl CanBeAnimated<'_, Scene> for Animated {
+	fn get_animation_map(&mut self) -> &mut HashMap<String, Animation> {
+		&mut self.map
+	}
+
+	fn get_animate_target(&mut self) -> &mut Scene {
+		&mut self.target
+	}
+}
+
+/// every value of a map with 10000 entries follows one animation
+fn large_map(c: &mut Criterion) {
+	let animation = Animation {
+		start_time: Duration::ZERO,
+		start_value: 0.0,
+		points: vec![(Duration::seconds(1), 1.0)],
+	};
+	let mut animated = Animated {
+		target: Scene { values: (0..10_000).map(|index| (format!("entry {}", index), 0.0)).collect() },
+		map: AnimationMapBuilder::new("Scene").field("values").animation(animation).build(),
+	};
+	c.bench_function("animate a map of 10000 entries", |b| b.iter(|| {
+		animated.caculate(black_box(&Duration::milliseconds(500))).unwrap();
+	}));
+}
+
+criterion_group!(benches, large_map);
+criterion_main!(benches);
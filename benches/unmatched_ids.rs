@@ -0,0 +1,65 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use nablo_data::*;
+use nablo_shape::prelude::Animation;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::hint::black_box;
+use time::Duration;
+
+#[derive(Serialize, Deserialize)]
+struct Scene {
+	values: BTreeMap<String, f32>,
+}
+
+struct Animated {
+	target: Scene,
+	map: HashMap<String, Animation>,
+	cache: Option<MatchCache>,
+}
+
+impl CanBeAnimated<'_, Scene> for Animated {
+	fn get_animation_map(&mut self) -> &mut HashMap<String, Animation> {
+		&mut self.map
+	}
+
+	fn get_animate_target(&mut self) -> &mut Scene {
+		&mut self.target
+	}
+
+	fn get_match_cache(&mut self) -> Option<&mut MatchCache> {
+		self.cache.as_mut()
+	}
+}
+
+/// a map with 10000 entries and a animation whose id matches none of them
+fn animated(cache: bool) -> Animated {
+	let animation = Animation {
+		start_time: Duration::ZERO,
+		start_value: 0.0,
+		points: vec![(Duration::seconds(1), 1.0)],
+	};
+	Animated {
+		target: Scene { values: (0..10_000).map(|index| (format!("entry {}", index), 0.0)).collect() },
+		map: AnimationMapBuilder::new("Scene").field("missing").animation(animation).build(),
+		cache: cache.then(MatchCache::default),
+	}
+}
+
+fn unmatched_ids(c: &mut Criterion) {
+	let mut group = c.benchmark_group("no id matches a map of 10000 entries");
+	for cache in [false, true] {
+		let mut animated = animated(cache);
+		let name = if cache { "with match cache" } else { "without match cache" };
+		group.bench_function(name, |b| b.iter(|| {
+			animated.caculate(black_box(&Duration::milliseconds(500))).unwrap();
+		}));
+	}
+	group.finish();
+}
+
+criterion_group!(benches, unmatched_ids);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! derive macros for `nablo_data`, use them through the `derive` feature of `nablo_data`
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Error;
+use syn::Field;
+use syn::Fields;
+use syn::GenericParam;
+use syn::Lifetime;
+use syn::LifetimeParam;
+
+/// implement `CanBeAnimated` for a struct that has a field marked `#[animate]` (the target) and a field marked `#[animations]` (the `HashMap<String, Animation>` returned by `get_animation_map`).
+///
+/// ```ignore
+/// #[derive(CanBeAnimated)]
+/// struct Button {
+///     #[animate]
+///     style: Style,
+///     #[animations]
+///     animations: HashMap<String, Animation>,
+/// }
+/// ```
+#[proc_macro_derive(CanBeAnimated, attributes(animate, animations))]
+pub fn derive_can_be_animated(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match can_be_animated(input) {
+		Ok(output) => output.into(),
+		Err(error) => error.to_compile_error().into(),
+	}
+}
+
+fn can_be_animated(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+	let fields = match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => &fields.named,
+			_ => return Err(Error::new_spanned(&input.ident, "CanBeAnimated can only be derived for structs with named fields")),
+		},
+		_ => return Err(Error::new_spanned(&input.ident, "CanBeAnimated can only be derived for structs")),
+	};
+	let target = marked_field(fields.iter(), "animate", &input)?;
+	let map = marked_field(fields.iter(), "animations", &input)?;
+	let (target_name, target_type) = (&target.ident, &target.ty);
+	let (map_name, map_type) = (&map.ident, &map.ty);
+
+	let name = &input.ident;
+	let (_, type_generics, _) = input.generics.split_for_impl();
+	let mut generics = input.generics.clone();
+	let lifetime = Lifetime::new("'__nablo", Span::call_site());
+	generics.params.insert(0, GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())));
+	let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::nablo_data::CanBeAnimated<#lifetime, #target_type> for #name #type_generics #where_clause {
+			fn get_animation_map(&mut self) -> &mut #map_type {
+				&mut self.#map_name
+			}
+
+			fn get_animate_target(&mut self) -> &mut #target_type {
+				&mut self.#target_name
+			}
+		}
+	})
+}
+
+/// the only field with `#[attribute]`
+fn marked_field<'a>(fields: impl Iterator<Item = &'a Field>, attribute: &str, input: &DeriveInput) -> Result<&'a Field, Error> {
+	let mut found = fields.filter(|field| field.attrs.iter().any(|attr| attr.path().is_ident(attribute)));
+	let field = found.next().ok_or_else(|| Error::new_spanned(&input.ident, format!("CanBeAnimated needs a field marked #[{}]", attribute)))?;
+	if let Some(other) = found.next() {
+		return Err(Error::new_spanned(&other.ident, format!("only one field can be marked #[{}]", attribute)));
+	}
+	Ok(field)
+}
@@ -0,0 +1,459 @@
+//! animate fields of your structs with [`Animation`], needs `std` feature
+
+use crate::clamp;
+use crate::from_data_with_max_depth;
+use crate::join_id;
+use crate::to_data_limited;
+use crate::DataEnum;
+use crate::Error;
+use crate::ParsedData;
+use crate::MAX_DEPTH;
+use nablo_shape::prelude::Animation;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+use time::Duration;
+
+/// build a animation map for [`CanBeAnimated`] without writing ids by hand.
+///
+/// ```ignore
+/// let map = AnimationMapBuilder::new("Player")
+///     .field("position").field("x").animation(x_animation)
+///     .field("colors").index(2).animation(color_animation)
+///     // every component of a `[f32; 3]` follows the same animation
+///     .field("scale").animation(scale_animation)
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AnimationMapBuilder {
+	root: String,
+	path: Vec<String>,
+	map: HashMap<String, Animation>,
+}
+
+impl AnimationMapBuilder {
+	/// `root` is the name of the animated struct
+	pub fn new(root: impl Into<String>) -> Self {
+		Self {
+			root: root.into(),
+			..Default::default()
+		}
+	}
+
+	/// step into a field
+	pub fn field(mut self, name: impl Into<String>) -> Self {
+		self.path.push(name.into());
+		self
+	}
+
+	/// step into a element of a sequence or tuple
+	pub fn index(self, index: usize) -> Self {
+		self.field(index.to_string())
+	}
+
+	/// set the animation of current field, then go back to the root. if the field is a struct or sequence, the animation is shared by its int and float fields
+	pub fn animation(mut self, animation: Animation) -> Self {
+		let id = self.path.iter().fold(join_id("", &self.root), |id, name| join_id(&id, name));
+		self.map.insert(id, animation);
+		self.path.clear();
+		self
+	}
+
+	pub fn build(self) -> HashMap<String, Animation> {
+		self.map
+	}
+}
+
+/// how animated int fields turn the float value of a animation into a int, see [`CanBeAnimated::get_int_rounding_map`]
+///
+/// animations run on floats, so ints are only exact up to `2^24` (`2^53` for the math in between). further out the animation can only reach the nearest float,
+/// a value that already rounds to the animation value is kept as is instead of jumping to that float, and the output is always clamped into the range of the field
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum IntRounding {
+	/// drop the fractional part
+	#[default] Trunc,
+	/// round to the nearest int, halves round away from zero
+	Nearest,
+	/// round toward negative infinity
+	Floor,
+	/// round toward positive infinity
+	Ceil,
+	/// keep the current value until the animation is at least one unit away from it, then round to the nearest int.
+	/// slow animations step by whole units instead of jumping whenever they cross a rounding boundary
+	Stepped,
+}
+
+impl IntRounding {
+	fn apply(&self, x: f64, current: i128) -> i128 {
+		// `current as f64` is the nearest float, so this only matches when `x` can not tell the two apart
+		if x.is_nan() || (current.unsigned_abs() > 1 << 53 && x == current as f64) {
+			return current;
+		}
+		match self {
+			Self::Trunc => x as i128,
+			Self::Nearest => x.round() as i128,
+			Self::Floor => x.floor() as i128,
+			Self::Ceil => x.ceil() as i128,
+			Self::Stepped => if (x - current as f64).abs() >= 1.0 {
+				x.round() as i128
+			}else {
+				current
+			},
+		}
+	}
+}
+
+/// how a animation plays once `duration` is past its end, see [`CanBeAnimated::get_play_mode_map`]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum PlayMode {
+	/// play once and keep the end value afterwards
+	#[default] Once,
+	/// start over from the start value every time the animation ends
+	Loop,
+	/// play forward, then backward, then forward again and so on. one cycle takes twice the length of the animation
+	PingPong,
+}
+
+impl PlayMode {
+	/// fold `duration` into the first cycle of `animation`, before the start of the animation it is left as is
+	fn fold(&self, animation: &Animation, duration: &Duration) -> Duration {
+		let len = animation.len().whole_nanoseconds();
+		let elapsed = (*duration - animation.start_time).whole_nanoseconds();
+		if *self == Self::Once || len <= 0 || elapsed <= 0 {
+			return *duration;
+		}
+		let local = match self {
+			Self::Loop => elapsed % len,
+			_ => {
+				let local = elapsed % (len * 2);
+				if local > len { len * 2 - local } else { local }
+			},
+		};
+		animation.start_time + Duration::nanoseconds(local.try_into().unwrap_or(i64::MAX))
+	}
+}
+
+/// remembers that the ids of a animation map and variant map matched no field of the target, so [`CanBeAnimated::caculate`] can skip serializing the target on later frames, see [`CanBeAnimated::get_match_cache`].
+/// the cache assumes the target keeps its shape, call [`MatchCache::clear`] when a field appears that the ids may match, such as a element pushed into a animated sequence
+#[derive(Clone, Debug, Default)]
+pub struct MatchCache {
+	/// ids of the animation map and the variant map
+	unmatched: Option<(HashSet<String>, HashSet<String>)>,
+}
+
+impl MatchCache {
+	/// forget the cached ids, the next frame serializes the target again
+	pub fn clear(&mut self) {
+		self.unmatched = None;
+	}
+}
+
+/// whether `ids` are the same as `cached`
+fn same_ids<V>(cached: &HashSet<String>, ids: Option<&HashMap<String, V>>) -> bool {
+	match ids {
+		Some(ids) => cached.len() == ids.len() && ids.keys().all(|id| cached.contains(id)),
+		None => cached.is_empty(),
+	}
+}
+
+/// values of animations at a point of their timeline, keyed by the id they are registered under and the duration after [`PlayMode::fold`].
+/// targets with equal animation maps share one in [`advance_all`], so each value is only computed once per frame
+type AnimationValues = HashMap<String, HashMap<Duration, Option<f32>>>;
+
+/// the value of `t` registered under `key` at `duration`, taken from `values` if it was computed before
+fn animation_value(key: &str, t: &Animation, duration: &Duration, values: &mut AnimationValues) -> Option<f32> {
+	if let Some(x) = values.get(key).and_then(|inner| inner.get(duration)) {
+		return *x;
+	}
+	let x = t.caculate(duration);
+	values.entry(key.to_string()).or_default().insert(*duration, x);
+	x
+}
+
+/// per field options of [`CanBeAnimated`], borrowed once before walking the data
+#[derive(Default)]
+struct FieldOptions<'a> {
+	ranges: Option<&'a HashMap<String, RangeInclusive<f64>>>,
+	texts: Option<&'a HashMap<String, String>>,
+	variants: Option<&'a HashMap<String, Vec<(Duration, DataEnum)>>>,
+	roundings: Option<&'a HashMap<String, IntRounding>>,
+	modes: Option<&'a HashMap<String, PlayMode>>,
+}
+
+pub trait CanBeAnimated<'a, T> where
+	T: serde::Serialize + serde::Deserialize<'a>
+{
+	/// animations of fields, keyed by the id of the field, see [`AnimationMapBuilder`].
+	/// a animation keyed by a struct, sequence, map or enum animates every int and float field beneath it that has no animation of its own, so `x`, `y` and `z` of a position can share one timeline
+	fn get_animation_map(&mut self) -> &mut HashMap<String, Animation>;
+	fn get_animate_target(&mut self) -> &mut T;
+	/// ranges to clamp animated float fields into, using the same keys as [`CanBeAnimated::get_animation_map`]. float fields without a range will not be clamped
+	fn get_float_range_map(&self) -> Option<&HashMap<String, RangeInclusive<f64>>> {
+		None
+	}
+	/// full texts of animated string fields, using the same keys as [`CanBeAnimated::get_animation_map`].
+	/// string fields are revealed character by character, the value of the animation is the progress from `0.0` (empty) to `1.0` (full text).
+	/// a animated string field without a text here is left untouched, as its current value may already be cut by a earlier frame
+	fn get_text_map(&self) -> Option<&HashMap<String, String>> {
+		None
+	}
+	/// variant switches of enum fields, using the same keys as [`CanBeAnimated::get_animation_map`].
+	/// each entry is a list of `(time, value)`, once `duration` reaches `time` the enum field is replaced by `value`, the latest reached one wins and the field is left untouched before the first one.
+	/// `value` carries the inner values of the new variant, usually made by `to_data(&variant)?.data`
+	fn get_variant_map(&self) -> Option<&HashMap<String, Vec<(Duration, DataEnum)>>> {
+		None
+	}
+	/// how animated int fields are rounded, using the same keys as [`CanBeAnimated::get_animation_map`]. int fields without a entry use [`IntRounding::Trunc`]
+	fn get_int_rounding_map(&self) -> Option<&HashMap<String, IntRounding>> {
+		None
+	}
+	/// how animations play after their end, using the same keys as [`CanBeAnimated::get_animation_map`]. animations without a entry use [`PlayMode::Once`].
+	/// before the start of a animation every mode uses its start value, only [`PlayMode::Once`] ever reaches the end and keeps the end value, the others keep repeating
+	fn get_play_mode_map(&self) -> Option<&HashMap<String, PlayMode>> {
+		None
+	}
+	/// a cache of ids that matched no field, so frames where nothing can be animated do not serialize the target at all. without a cache the target is serialized every frame to find out
+	fn get_match_cache(&mut self) -> Option<&mut MatchCache> {
+		None
+	}
+	/// how deeply the target can be nested, see [`from_data_with_max_depth`]. deeper targets fail with [`Error::DepthLimitExceeded`], large limits can overflow the stack
+	fn get_max_depth(&self) -> usize {
+		MAX_DEPTH
+	}
+
+	/// animate the target. if no id in the animation map matches a field of the target, the target will be left untouched without being deserialized, and with a [`CanBeAnimated::get_match_cache`] later frames do not serialize it either
+	fn caculate(&mut self, duration: &Duration) -> Result<(), Error> {
+		caculate_with(self, duration, &mut AnimationValues::new())
+	}
+
+	/// same as [`CanBeAnimated::caculate`], but returns the animated value instead of writing it into the target, so it can be checked before being used. the target is left untouched
+	fn caculate_to(&mut self, duration: &Duration) -> Result<T, Error> {
+		let max_depth = self.get_max_depth();
+		let mut parsed_data = match animated_data(self, duration, &mut AnimationValues::new())? {
+			Some((parsed_data, _)) => parsed_data,
+			None => to_data_limited(self.get_animate_target(), max_depth)?,
+		};
+		from_data_with_max_depth(&mut parsed_data, max_depth)
+	}
+
+	/// same as [`CanBeAnimated::caculate`], but driven by a progress from `0.0` to `1.0` instead of a time, which suits scrubbing.
+	/// all animations and variant switches share one timeline, from the earliest start to the latest end, so animations of different lengths keep their timing relative to each other.
+	/// progress outside of `0.0..=1.0` is clamped, NaN is treated as `0.0`
+	fn caculate_progress(&mut self, progress: f64) -> Result<(), Error> {
+		let bounds = timeline_bounds(self.get_animation_map().values().filter(|t| !t.is_empty()).map(|t| (t.start_time, t.start_time + t.len())));
+		let switches = timeline_bounds(self.get_variant_map().into_iter().flat_map(HashMap::values).flatten().map(|(time, _)| (*time, *time)));
+		let duration = progress_duration(bounds.into_iter().chain(switches), progress);
+		self.caculate(&duration)
+	}
+}
+
+/// the earliest start and the latest end of `bounds`
+fn timeline_bounds(bounds: impl Iterator<Item = (Duration, Duration)>) -> Option<(Duration, Duration)> {
+	bounds.reduce(|(start, end), (inner_start, inner_end)| (start.min(inner_start), end.max(inner_end)))
+}
+
+/// the time at `progress` of the timeline covering `bounds` of every animation and variant switch, see [`CanBeAnimated::caculate_progress`]
+fn progress_duration(bounds: impl Iterator<Item = (Duration, Duration)>, progress: f64) -> Duration {
+	let (start, end) = match timeline_bounds(bounds) {
+		Some(inner) => inner,
+		None => return Duration::ZERO,
+	};
+	let progress = if progress.is_nan() { 0.0 } else { progress.clamp(0.0, 1.0) };
+	start + (end - start) * progress
+}
+
+/// [`CanBeAnimated::caculate`], with `values` of animations computed for targets with the same animation map
+fn caculate_with<'a, T, A>(animated: &mut A, duration: &Duration, values: &mut AnimationValues) -> Result<(), Error> where
+	T: serde::Serialize + serde::Deserialize<'a>,
+	A: CanBeAnimated<'a, T> + ?Sized,
+{
+	if let Some((mut parsed_data, true)) = animated_data(animated, duration, values)? {
+		let max_depth = animated.get_max_depth();
+		*animated.get_animate_target() = from_data_with_max_depth(&mut parsed_data, max_depth)?;
+	}
+	Ok(())
+}
+
+/// the target animated at `duration`, and whether any field was animated. `None` if there is nothing to animate, without serializing the target
+fn animated_data<'a, T, A>(animated: &mut A, duration: &Duration, values: &mut AnimationValues) -> Result<Option<(ParsedData, bool)>, Error> where
+	T: serde::Serialize + serde::Deserialize<'a>,
+	A: CanBeAnimated<'a, T> + ?Sized,
+{
+	if animated.get_animation_map().is_empty() && animated.get_variant_map().is_none_or(HashMap::is_empty) {
+		return Ok(None)
+	}
+	// the ids are taken out of the cache, so it is not borrowed while the maps are
+	if let Some((animations, variants)) = animated.get_match_cache().and_then(|cache| cache.unmatched.take()) {
+		if same_ids(&animations, Some(animated.get_animation_map())) && same_ids(&variants, animated.get_variant_map()) {
+			if let Some(cache) = animated.get_match_cache() {
+				cache.unmatched = Some((animations, variants));
+			}
+			return Ok(None)
+		}
+	}
+	let max_depth = animated.get_max_depth();
+	let mut parsed_data = to_data_limited(animated.get_animate_target(), max_depth)?;
+	let cached = animated.get_match_cache().is_some();
+	// the animation map is moved out while the options are borrowed from `animated`, so none of them is cloned, and moved back afterwards
+	let map = core::mem::take(animated.get_animation_map());
+	let options = FieldOptions {
+		ranges: animated.get_float_range_map(),
+		texts: animated.get_text_map(),
+		variants: animated.get_variant_map(),
+		roundings: animated.get_int_rounding_map(),
+		modes: animated.get_play_mode_map(),
+	};
+	let matched = animation_caculate("", &mut parsed_data, duration, &map, &options, None, values);
+	// ids are only collected when the match cache keeps them
+	let unmatched = (!matched && cached).then(|| (
+		map.keys().cloned().collect(),
+		options.variants.into_iter().flat_map(HashMap::keys).cloned().collect(),
+	));
+	*animated.get_animation_map() = map;
+	if let (Some(unmatched), Some(cache)) = (unmatched, animated.get_match_cache()) {
+		cache.unmatched = Some(unmatched);
+	}
+	Ok(Some((parsed_data, matched)))
+}
+
+/// animate every target at the same `duration` the way [`CanBeAnimated::caculate`] does by default, such as all animated widgets of a scene in one frame.
+/// targets with equal animation maps are grouped, each value of their animations is computed once for the whole group. returns the number of groups.
+/// stops at the first target that fails, its error is wrapped in a [`Error::At`] named after its index in `targets`
+pub fn advance_all<'a, T>(targets: &mut [&mut dyn CanBeAnimated<'a, T>], duration: &Duration) -> Result<usize, Error> where
+	T: serde::Serialize + serde::Deserialize<'a>,
+{
+	// the index of the first target of each group, and the values computed for the group
+	let mut groups: Vec<(usize, AnimationValues)> = Vec::new();
+	for index in 0..targets.len() {
+		let (before, after) = targets.split_at_mut(index);
+		let target = &mut after[0];
+		let map = target.get_animation_map();
+		let group = match groups.iter().position(|(first, _)| before[*first].get_animation_map() == map) {
+			Some(group) => group,
+			None => {
+				groups.push((index, AnimationValues::new()));
+				groups.len() - 1
+			},
+		};
+		caculate_with(&mut **target, duration, &mut groups[group].1).map_err(|error| error.at(&index.to_string()))?;
+	}
+	Ok(groups.len())
+}
+
+/// ids of every int and float field of the default value of `T`, in field order. these are the keys to register animations under, see [`CanBeAnimated::get_animation_map`].
+/// sequences, maps and `None` options are empty by default, so fields inside them are not listed
+pub fn animatable_fields<T: Serialize + Default>() -> Result<Vec<String>, Error> {
+	let data = to_data_limited(&T::default(), MAX_DEPTH)?;
+	Ok(data.leaves().filter(|(_, data)| matches!(data, DataEnum::Int(_, _) | DataEnum::Float(_))).map(|(id, _)| id).collect())
+}
+
+/// sample how a field of `target` changes with the animations in `map`, without touching `target`. `field` is a path in the format of [`ParsedData::get_path`], the output contains one value for each of `times`
+pub fn sample_field<T: Serialize>(target: &T, map: &HashMap<String, Animation>, field: &str, times: &[Duration]) -> Result<Vec<DataEnum>, Error> {
+	let data = to_data_limited(target, MAX_DEPTH)?;
+	let options = FieldOptions::default();
+	let mut values = AnimationValues::new();
+	let mut output = Vec::with_capacity(times.len());
+	for duration in times {
+		let mut data = data.clone();
+		animation_caculate("", &mut data, duration, map, &options, None, &mut values);
+		match data.get_path(field) {
+			Some(inner) => output.push(inner.data.clone()),
+			None => return Err(Error::UnknownField(field.to_string())),
+		}
+	}
+	Ok(output)
+}
+
+/// returns `true` if any field has a animation
+/// `group` is the animation (with its id and play mode) of the closest parent that has one, used by numeric fields without their own animation
+fn animation_caculate(id: &str, data: &mut ParsedData, duration: &Duration, map: &HashMap<String, Animation>, options: &FieldOptions, group: Option<(&str, &Animation, PlayMode)>, values: &mut AnimationValues) -> bool {
+	let id = join_id(id, &data.name);
+	let group = match map.get_key_value(&id) {
+		Some((key, t)) => Some((key.as_str(), t, options.modes.and_then(|modes| modes.get(&id)).copied().unwrap_or_default())),
+		None => group,
+	};
+	match &mut data.data {
+		DataEnum::Node(inner) => {
+			let mut matched = false;
+			for inside in inner {
+				matched |= animation_caculate(&id, inside, duration, map, options, group, values);
+			}
+			return matched;
+		},
+		DataEnum::Map(box_inside) => {
+			return animation_caculate(&id, &mut box_inside.1, duration, map, options, group, values);
+		},
+		DataEnum::Enum(_, _) => {
+			let mut matched = false;
+			if let Some(keyframes) = options.variants.and_then(|variants| variants.get(&id)) {
+				// matched even before the first switch, so [`MatchCache`] does not skip the field until then
+				matched = true;
+				if let Some((_, value)) = keyframes.iter().filter(|(time, _)| time <= duration).max_by_key(|(time, _)| *time) {
+					data.data = value.clone();
+				}
+			}
+			let inner = match &mut data.data {
+				DataEnum::Enum(_, inner) => inner,
+				_ => return matched,
+			};
+			for inside in inner {
+				matched |= animation_caculate(&id, inside, duration, map, options, group, values);
+			}
+			return matched;
+		},
+		DataEnum::Int(value, range) => {
+			if let Some((key, t, mode)) = group {
+				let duration = &mode.fold(t, duration);
+				let x = if let Some(x) = animation_value(key, t, duration, values) {
+					x
+				}else if duration > &(t.len() + t.start_time) && !t.is_empty() {
+					t.end_value()
+				}else if duration < &t.start_time && !t.is_empty() {
+					t.start_value
+				}else {
+					return true;
+				};
+				let rounding = options.roundings.and_then(|roundings| roundings.get(&id)).copied().unwrap_or_default();
+				*value = clamp(rounding.apply(x as f64, *value), range);
+			}
+		},
+		DataEnum::Float(value) => {
+			if let Some((key, t, mode)) = group {
+				let duration = &mode.fold(t, duration);
+				let x = if let Some(x) = animation_value(key, t, duration, values) {
+					x as f64
+				}else if duration > &(t.len() + t.start_time) && !t.is_empty() {
+					t.end_value() as f64
+				}else if duration < &t.start_time && !t.is_empty() {
+					t.start_value as f64
+				}else {
+					return true;
+				};
+				*value = match options.ranges.and_then(|ranges| ranges.get(&id)) {
+					Some(range) => clamp(x, range),
+					None => x,
+				};
+			}
+		},
+		DataEnum::String(value) => {
+			if let (Some((key, t, mode)), true, Some(full)) = (group, map.contains_key(&id), options.texts.and_then(|texts| texts.get(&id))) {
+				let duration = &mode.fold(t, duration);
+				let progress = if let Some(x) = animation_value(key, t, duration, values) {
+					x as f64
+				}else if duration > &(t.len() + t.start_time) && !t.is_empty() {
+					1.0
+				}else if duration < &t.start_time && !t.is_empty() {
+					t.start_value as f64
+				}else {
+					return true;
+				};
+				let len = (full.chars().count() as f64 * clamp(progress, &(0.0..=1.0))).round() as usize;
+				let revealed = full.chars().take(len).collect();
+				*value = revealed;
+			}
+		},
+		_ => {}
+	}
+	map.contains_key(&id) || (group.is_some() && matches!(data.data, DataEnum::Int(_, _) | DataEnum::Float(_)))
+}
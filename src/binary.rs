@@ -0,0 +1,250 @@
+//! a compact binary format for [`ParsedData`]
+//!
+//! every [`ParsedData`] is written as its name, a tag byte and the payload of the tag. all numbers are little endian, lengths and counts are `u32`, strings are a length followed by utf-8 bytes.
+//!
+//! | tag | variant | payload |
+//! | --- | --- | --- |
+//! | 0 | [`DataEnum::Node`] | count, then every child |
+//! | 1 | [`DataEnum::Map`] | key, then value |
+//! | 2 | [`DataEnum::Enum`] | variant as string, count, then every child |
+//! | 3 | [`DataEnum::Data`] | length, then bytes |
+//! | 4 | [`DataEnum::String`] | string |
+//! | 5 | [`DataEnum::Int`] | value, range start and range end, each as `i128` |
+//! | 6 | [`DataEnum::Float`] | `f64` |
+//! | 7 | [`DataEnum::Bool`] | `0` or `1` |
+//! | 8 | [`DataEnum::None`] | nothing |
+//! | 9 | [`DataEnum::Some`] | the wrapped value |
+//! | 10 | [`DataEnum::Int`] with range `i128::MIN..=i128::MAX` | value as `i128` |
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::is_valid_int;
+use crate::DataEnum;
+use crate::Error;
+use crate::MAX_DEPTH;
+use crate::ParsedData;
+
+const NODE: u8 = 0;
+const MAP: u8 = 1;
+const ENUM: u8 = 2;
+const DATA: u8 = 3;
+const STRING: u8 = 4;
+const INT: u8 = 5;
+const FLOAT: u8 = 6;
+const BOOL: u8 = 7;
+const NONE: u8 = 8;
+const SOME: u8 = 9;
+const INT_FULL: u8 = 10;
+
+/// encode a [`ParsedData`] into bytes, see [`from_bytes`] for the reverse.
+/// returns [`Error::SizeLimitExceeded`] if a string, byte array or child count is longer than `u32::MAX`, which the format can not store
+pub fn to_bytes(data: &ParsedData) -> Result<Vec<u8>, Error> {
+	to_bytes_bounded(data, usize::MAX)
+}
+
+/// same as [`to_bytes`], but returns [`Error::SizeLimitExceeded`] as soon as the output would be longer than `max_len` bytes, before anything past the limit is allocated
+pub fn to_bytes_bounded(data: &ParsedData, max_len: usize) -> Result<Vec<u8>, Error> {
+	let mut writer = Writer { output: vec!(), max_len };
+	writer.data(data)?;
+	Ok(writer.output)
+}
+
+/// decode bytes made by [`to_bytes`]. returns [`Error::Syntax`] if the bytes are malformed or have bytes left at the end,
+/// and [`Error::DepthLimitExceeded`] if they are nested deeper than [`MAX_DEPTH`], as measured by [`ParsedData::depth`]
+pub fn from_bytes(bytes: &[u8]) -> Result<ParsedData, Error> {
+	let mut reader = Reader { bytes, depth: 0 };
+	let data = reader.data()?;
+	if reader.bytes.is_empty() {
+		Ok(data)
+	}else {
+		Err(Error::Syntax)
+	}
+}
+
+/// pack a subtree into a [`DataEnum::Data`] with [`to_bytes`], so a parent can carry an already parsed child as opaque bytes. see [`decode_subtree`] for the reverse
+pub fn encode_subtree(data: &ParsedData) -> Result<DataEnum, Error> {
+	Ok(DataEnum::Data(to_bytes(data)?))
+}
+
+/// unpack a subtree made by [`encode_subtree`]. returns [`Error::UnexpectedType`] if `data` is not a [`DataEnum::Data`] and [`Error::Syntax`] if its bytes are malformed
+pub fn decode_subtree(data: &DataEnum) -> Result<ParsedData, Error> {
+	match data {
+		DataEnum::Data(bytes) => from_bytes(bytes),
+		_ => Err(Error::UnexpectedType(String::from("data"), data.type_name())),
+	}
+}
+
+struct Writer {
+	output: Vec<u8>,
+	max_len: usize,
+}
+
+impl Writer {
+	fn bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+		if bytes.len() > self.max_len - self.output.len() {
+			return Err(Error::SizeLimitExceeded(self.max_len));
+		}
+		self.output.extend_from_slice(bytes);
+		Ok(())
+	}
+
+	fn tag(&mut self, tag: u8) -> Result<(), Error> {
+		self.bytes(&[tag])
+	}
+
+	fn len(&mut self, len: usize) -> Result<(), Error> {
+		let len = u32::try_from(len).map_err(|_| Error::SizeLimitExceeded(u32::MAX as usize))?;
+		self.bytes(&len.to_le_bytes())
+	}
+
+	fn str(&mut self, input: &str) -> Result<(), Error> {
+		self.len(input.len())?;
+		self.bytes(input.as_bytes())
+	}
+
+	fn data(&mut self, data: &ParsedData) -> Result<(), Error> {
+		self.str(&data.name)?;
+		match &data.data {
+			DataEnum::Node(inner) => {
+				self.tag(NODE)?;
+				self.len(inner.len())?;
+				for inside in inner {
+					self.data(inside)?;
+				}
+			},
+			DataEnum::Map(box_inside) => {
+				self.tag(MAP)?;
+				self.data(&box_inside.0)?;
+				self.data(&box_inside.1)?;
+			},
+			DataEnum::Enum(variant, inner) => {
+				self.tag(ENUM)?;
+				self.str(variant)?;
+				self.len(inner.len())?;
+				for inside in inner {
+					self.data(inside)?;
+				}
+			},
+			DataEnum::Data(inner) => {
+				self.tag(DATA)?;
+				self.len(inner.len())?;
+				self.bytes(inner)?;
+			},
+			DataEnum::String(inner) => {
+				self.tag(STRING)?;
+				self.str(inner)?;
+			},
+			DataEnum::Int(inner, range) if *range == (i128::MIN..=i128::MAX) => {
+				self.tag(INT_FULL)?;
+				self.bytes(&inner.to_le_bytes())?;
+			},
+			DataEnum::Int(inner, range) => {
+				self.tag(INT)?;
+				self.bytes(&inner.to_le_bytes())?;
+				self.bytes(&range.start().to_le_bytes())?;
+				self.bytes(&range.end().to_le_bytes())?;
+			},
+			DataEnum::Float(inner) => {
+				self.tag(FLOAT)?;
+				self.bytes(&inner.to_le_bytes())?;
+			},
+			DataEnum::Bool(inner) => {
+				self.tag(BOOL)?;
+				self.tag(*inner as u8)?;
+			},
+			DataEnum::Some(inner) => {
+				self.tag(SOME)?;
+				self.data(inner)?;
+			},
+			DataEnum::None => self.tag(NONE)?,
+		}
+		Ok(())
+	}
+}
+
+struct Reader<'a> {
+	bytes: &'a [u8],
+	depth: usize,
+}
+
+impl<'a> Reader<'a> {
+	fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+		if self.bytes.len() < len {
+			return Err(Error::Syntax);
+		}
+		let (output, rest) = self.bytes.split_at(len);
+		self.bytes = rest;
+		Ok(output)
+	}
+
+	fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+		let mut output = [0; N];
+		output.copy_from_slice(self.take(N)?);
+		Ok(output)
+	}
+
+	fn len(&mut self) -> Result<usize, Error> {
+		Ok(u32::from_le_bytes(self.array()?) as usize)
+	}
+
+	fn i128(&mut self) -> Result<i128, Error> {
+		Ok(i128::from_le_bytes(self.array()?))
+	}
+
+	fn string(&mut self) -> Result<String, Error> {
+		let len = self.len()?;
+		String::from_utf8(self.take(len)?.to_vec()).map_err(|_| Error::Syntax)
+	}
+
+	fn children(&mut self) -> Result<Vec<ParsedData>, Error> {
+		let len = self.len()?;
+		// every child takes at least 5 bytes, so a broken count can not allocate too much memory
+		let mut output = Vec::with_capacity(len.min(self.bytes.len() / 5));
+		for _ in 0..len {
+			output.push(self.data()?);
+		}
+		Ok(output)
+	}
+
+	fn data(&mut self) -> Result<ParsedData, Error> {
+		self.depth += 1;
+		if self.depth > MAX_DEPTH {
+			return Err(Error::DepthLimitExceeded(MAX_DEPTH));
+		}
+		let name = self.string()?;
+		let data = match self.array::<1>()?[0] {
+			NODE => DataEnum::Node(self.children()?),
+			MAP => DataEnum::Map(Box::new((self.data()?, self.data()?))),
+			ENUM => DataEnum::Enum(self.string()?, self.children()?),
+			DATA => {
+				let len = self.len()?;
+				DataEnum::Data(self.take(len)?.to_vec())
+			},
+			STRING => DataEnum::String(self.string()?),
+			INT => {
+				let (value, range) = (self.i128()?, self.i128()?..=self.i128()?);
+				if !is_valid_int(value, &range) {
+					return Err(Error::Syntax);
+				}
+				DataEnum::Int(value, range)
+			},
+			INT_FULL => DataEnum::Int(self.i128()?, i128::MIN..=i128::MAX),
+			FLOAT => DataEnum::Float(f64::from_le_bytes(self.array()?)),
+			BOOL => match self.array::<1>()?[0] {
+				0 => DataEnum::Bool(false),
+				1 => DataEnum::Bool(true),
+				_ => return Err(Error::Syntax),
+			},
+			NONE => DataEnum::None,
+			SOME => DataEnum::Some(Box::new(self.data()?)),
+			_ => return Err(Error::Syntax),
+		};
+		self.depth -= 1;
+		Ok(ParsedData {
+			data,
+			name
+		})
+	}
+}
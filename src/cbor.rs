@@ -0,0 +1,115 @@
+//! bridge between [`ParsedData`] and CBOR, needs `cbor` feature
+
+use crate::DataEnum;
+use crate::Error;
+use crate::ParsedData;
+use ciborium::Value;
+use ciborium::value::Integer;
+use std::collections::HashSet;
+
+/// write a [`ParsedData`] as CBOR.
+///
+/// * nodes of map entries become maps keyed by the whole key, nodes whose children all have different, non-empty names become maps with text keys, other nodes become arrays
+/// * map entries outside of a map become `[key, value]`
+/// * unit variants become text, other variants become maps with the variant name as the only key, like [`crate::to_json`] does
+/// * the range of [`DataEnum::Int`] is dropped, ints that CBOR can not hold (below `-2^64` or above `2^64 - 1`) are written as floats
+/// * [`DataEnum::Data`] is written as a byte string
+/// * [`DataEnum::Some`] is written as its inner value, so `Some(None)` becomes `null` as well
+pub fn to_cbor(data: &ParsedData) -> Result<Vec<u8>, Error> {
+	let mut output = vec!();
+	ciborium::into_writer(&to_cbor_value(data), &mut output).map_err(|e| Error::SerdeError(e.to_string()))?;
+	Ok(output)
+}
+
+/// read CBOR written by [`to_cbor`] or anything else into a [`ParsedData`].
+///
+/// like [`crate::from_json`], this is not the exact reverse of [`to_cbor`]:
+/// * maps whose keys are all text become nodes with named children, other maps become nodes of map entries
+/// * arrays become nodes with children named by their index
+/// * integers get the full range of `i128`, see [`crate::to_data_without_ranges`]
+/// * tags are dropped, only the tagged value is kept
+/// * `null` becomes [`DataEnum::None`]
+pub fn from_cbor(input: &[u8]) -> Result<ParsedData, Error> {
+	let value: Value = ciborium::from_reader(input).map_err(|e| Error::SerdeError(e.to_string()))?;
+	Ok(from_cbor_value(&value))
+}
+
+fn to_cbor_value(data: &ParsedData) -> Value {
+	match &data.data {
+		DataEnum::Node(inner) => node_to_cbor(inner),
+		DataEnum::Map(box_inside) => Value::Array(vec!(to_cbor_value(&box_inside.0), to_cbor_value(&box_inside.1))),
+		DataEnum::Enum(variant, inner) => {
+			if inner.is_empty() {
+				return Value::Text(variant.clone());
+			}
+			let value = if inner.len() == 1 && inner[0].name.is_empty() {
+				to_cbor_value(&inner[0])
+			}else {
+				node_to_cbor(inner)
+			};
+			Value::Map(vec!((Value::Text(variant.clone()), value)))
+		},
+		DataEnum::Data(inner) => Value::Bytes(inner.clone()),
+		DataEnum::String(inner) => Value::Text(inner.clone()),
+		DataEnum::Int(inner, _) => match Integer::try_from(*inner) {
+			Ok(t) => Value::Integer(t),
+			Err(_) => Value::Float(*inner as f64),
+		},
+		DataEnum::Float(inner) => Value::Float(*inner),
+		DataEnum::Bool(inner) => Value::Bool(*inner),
+		DataEnum::Some(inner) => to_cbor_value(inner),
+		DataEnum::None => Value::Null,
+	}
+}
+
+fn node_to_cbor(inner: &[ParsedData]) -> Value {
+	if !inner.is_empty() && inner.iter().all(|data| matches!(data.data, DataEnum::Map(_))) {
+		return Value::Map(inner.iter().map(|data| match &data.data {
+			DataEnum::Map(box_inside) => (to_cbor_value(&box_inside.0), to_cbor_value(&box_inside.1)),
+			_ => unreachable!(),
+		}).collect());
+	}
+	let mut names = HashSet::new();
+	let mut map = Vec::with_capacity(inner.len());
+	for data in inner {
+		if data.name.is_empty() || !names.insert(data.name.as_str()) {
+			return Value::Array(inner.iter().map(to_cbor_value).collect());
+		}
+		map.push((Value::Text(data.name.clone()), to_cbor_value(data)));
+	}
+	if map.is_empty() {
+		Value::Array(vec!())
+	}else {
+		Value::Map(map)
+	}
+}
+
+fn from_cbor_value(value: &Value) -> ParsedData {
+	match value {
+		Value::Integer(inner) => ParsedData {
+			data: DataEnum::Int(i128::from(*inner), i128::MIN..=i128::MAX),
+			name: String::new(),
+		},
+		Value::Bytes(inner) => inner.clone().into(),
+		Value::Float(inner) => (*inner).into(),
+		Value::Text(inner) => inner.as_str().into(),
+		Value::Bool(inner) => (*inner).into(),
+		Value::Null => ParsedData::default(),
+		Value::Tag(_, inner) => from_cbor_value(inner),
+		Value::Array(inner) => inner.iter().enumerate().map(|(index, value)| ParsedData {
+			name: index.to_string(),
+			..from_cbor_value(value)
+		}).collect::<Vec<ParsedData>>().into(),
+		Value::Map(inner) => {
+			if inner.iter().all(|(key, _)| key.is_text()) {
+				inner.iter().map(|(key, value)| ParsedData {
+					name: key.as_text().unwrap_or_default().to_string(),
+					..from_cbor_value(value)
+				}).collect::<Vec<ParsedData>>().into()
+			}else {
+				inner.iter().map(|(key, value)| ParsedData::map(from_cbor_value(key), from_cbor_value(value))).collect::<Vec<ParsedData>>().into()
+			}
+		},
+		_ => ParsedData::default(),
+	}
+}
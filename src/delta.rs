@@ -0,0 +1,529 @@
+//! find and apply differences between two values of the same type, needs `std` feature
+
+use crate::clamp;
+use crate::from_data_with_max_depth;
+use crate::join_id;
+use crate::to_data_limited;
+use crate::DataEnum;
+use crate::Error;
+use crate::ParsedData;
+use crate::MAX_DEPTH;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::ops::RangeInclusive;
+
+/// difference of a single field, see [`caculate_delta_value`]
+#[derive(PartialEq, Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum DeltaValue {
+	/// difference of a numeric field, left - right
+	Number(f64),
+	/// a bool field changed, contains the value of left
+	Bool(bool),
+	/// a string field changed, contains the value of left
+	String(String),
+	/// a field or map entry has a value in left but is `None` or missing in right, contains the value of left
+	Added(DataEnum),
+	/// a field or map entry is `None` or missing in left but has a value in right
+	Removed,
+}
+
+/// find difference for two structs, only avaluable for numeric fields. outputs left - right
+pub fn caculate_delta<T: Serialize>(left: &T, right: &T) -> Result<HashMap<String, f64>, Error> {
+	let mut map = HashMap::new();
+	caculate_delta_into(left, right, &mut map)?;
+	Ok(map)
+}
+
+/// same as [`caculate_delta`], but writes into `map` to reuse its allocation. `map` will be cleared first
+pub fn caculate_delta_into<T: Serialize>(left: &T, right: &T, map: &mut HashMap<String, f64>) -> Result<(), Error> {
+	caculate_delta_number(left, right, map, &DeltaOptions::default())
+}
+
+/// same as [`caculate_delta`], but leaves out fields whose id ends with one of `ignore`, together with every field beneath them.
+/// `ignore` can be full ids like `----Config----updated_at` or suffixes like `----updated_at`, start suffixes with `----` so `----count` does not match `----account`
+pub fn caculate_delta_filtered<T: Serialize>(left: &T, right: &T, ignore: &[&str]) -> Result<HashMap<String, f64>, Error> {
+	let mut map = HashMap::new();
+	caculate_delta_number(left, right, &mut map, &DeltaOptions {
+		ignore,
+		..Default::default()
+	})?;
+	Ok(map)
+}
+
+/// same as [`caculate_delta`], but float fields that differ by no more than `epsilon` are treated as equal and left out, so rounding noise like `0.1 + 0.2` against `0.3` is not reported
+pub fn caculate_delta_tol<T: Serialize>(left: &T, right: &T, epsilon: f64) -> Result<HashMap<String, f64>, Error> {
+	let mut map = HashMap::new();
+	caculate_delta_number(left, right, &mut map, &DeltaOptions {
+		epsilon,
+		..Default::default()
+	})?;
+	Ok(map)
+}
+
+/// same as [`caculate_delta`], but with your own depth limit instead of [`MAX_DEPTH`], see [`from_data_with_max_depth`]. large limits can overflow the stack
+pub fn caculate_delta_with_max_depth<T: Serialize>(left: &T, right: &T, max_depth: usize) -> Result<HashMap<String, f64>, Error> {
+	let mut map = HashMap::new();
+	caculate_delta_number(left, right, &mut map, &DeltaOptions {
+		max_depth,
+		..Default::default()
+	})?;
+	Ok(map)
+}
+
+fn caculate_delta_number<T: Serialize>(left: &T, right: &T, map: &mut HashMap<String, f64>, options: &DeltaOptions) -> Result<(), Error> {
+	map.clear();
+	let left = to_data_limited(left, options.max_depth)?;
+	let right = to_data_limited(right, options.max_depth)?;
+	caculate_delta_all(left, right, &mut |id, value| {
+		if let DeltaValue::Number(t) = value {
+			map.insert(id, t);
+		}
+	}, options)
+}
+
+/// a delta map from [`caculate_delta`], so it can not be mixed up with other maps keyed by field ids such as animation maps
+#[derive(PartialEq, Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct DeltaMap(pub HashMap<String, f64>);
+
+impl DeltaMap {
+	/// same as [`caculate_delta`]
+	pub fn caculate<T: Serialize>(left: &T, right: &T) -> Result<Self, Error> {
+		Ok(Self(caculate_delta(left, right)?))
+	}
+
+	/// same as [`apply_delta`]
+	pub fn apply<'a, T: Serialize+ Deserialize<'a>>(&self, target: &mut T) -> Result<(), Error> {
+		apply_delta(target, &self.0)
+	}
+
+	/// add the deltas of `other`, fields in both maps get the sum of both deltas
+	pub fn merge(&mut self, other: &DeltaMap) {
+		for (id, t) in &other.0 {
+			*self.0.entry(id.clone()).or_insert(0.0) += t;
+		}
+	}
+
+	/// multiply every delta by `factor`
+	pub fn scale(&mut self, factor: f64) {
+		for t in self.0.values_mut() {
+			*t *= factor;
+		}
+	}
+}
+
+impl From<HashMap<String, f64>> for DeltaMap {
+	fn from(input: HashMap<String, f64>) -> Self {
+		Self(input)
+	}
+}
+
+impl From<DeltaMap> for HashMap<String, f64> {
+	fn from(input: DeltaMap) -> Self {
+		input.0
+	}
+}
+
+/// entries of a delta map from [`caculate_delta`], largest absolute value first. entries with the same absolute value are ordered by their id
+pub fn sorted_deltas(map: &HashMap<String, f64>) -> Vec<(&String, f64)> {
+	let mut output: Vec<(&String, f64)> = map.iter().map(|(id, t)| (id, *t)).collect();
+	output.sort_by(|(lid, lt), (rid, rt)| rt.abs().total_cmp(&lt.abs()).then_with(|| lid.cmp(rid)));
+	output
+}
+
+/// find relative difference for two structs, only avaluable for numeric fields. outputs (left - right) / right, so `0.5` means left is 50% larger than right.
+/// like [`caculate_delta`], fields that did not change are left out, fields whose right value is zero are left out as well since they have no relative difference
+pub fn caculate_delta_percent<T: Serialize>(left: &T, right: &T) -> Result<HashMap<String, f64>, Error> {
+	let left = to_data_limited(left, MAX_DEPTH)?;
+	let right = to_data_limited(right, MAX_DEPTH)?;
+	let bases: HashMap<String, f64> = right.leaves().filter_map(|(id, data)| match data {
+		DataEnum::Int(_, _) | DataEnum::Float(_) => Some((id, data.as_f64()?)),
+		_ => None,
+	}).collect();
+	let mut map = HashMap::new();
+	caculate_delta_all(left, right, &mut |id, value| {
+		if let (DeltaValue::Number(t), Some(base)) = (value, bases.get(&id)) {
+			if *base != 0.0 {
+				map.insert(id, t / base);
+			}
+		}
+	}, &DeltaOptions::default())?;
+	Ok(map)
+}
+
+/// what to do when a delta is NaN or infinite
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum NonFinitePolicy {
+	/// leave the field out of the delta map, or leave the field unchanged when applying
+	#[default] Skip,
+	/// return [`Error::NonFinite`]
+	Error,
+}
+
+/// find difference for two structs, including numeric, bool and string fields. numeric fields outputs left - right, others outputs the value of left.
+/// map entries are matched by their key, entries and optional fields that only have a value on one side are reported as [`DeltaValue::Added`] or [`DeltaValue::Removed`]
+pub fn caculate_delta_value<T: Serialize>(left: &T, right: &T) -> Result<HashMap<String, DeltaValue>, Error> {
+	caculate_delta_value_with(left, right, NonFinitePolicy::Skip)
+}
+
+/// same as [`caculate_delta_value`], but lets you choose what to do with NaN or infinite deltas
+pub fn caculate_delta_value_with<T: Serialize>(left: &T, right: &T, policy: NonFinitePolicy) -> Result<HashMap<String, DeltaValue>, Error> {
+	let left = to_data_limited(left, MAX_DEPTH)?;
+	let right = to_data_limited(right, MAX_DEPTH)?;
+	let mut map = HashMap::new();
+	caculate_delta_all(left, right, &mut |id, value| {
+		map.insert(id, value);
+	}, &DeltaOptions {
+		policy,
+		..Default::default()
+	})?;
+	Ok(map)
+}
+
+/// `true` if [`caculate_delta_value`] would find any difference, but stops at the first one without building a map
+pub fn has_changes<T: Serialize>(left: &T, right: &T) -> Result<bool, Error> {
+	let left = to_data_limited(left, MAX_DEPTH)?;
+	let right = to_data_limited(right, MAX_DEPTH)?;
+	let flow = caculate_delta_data(left, right, &mut |_, _| ControlFlow::Break(()), String::new(), &DeltaOptions::default())?;
+	Ok(flow.is_break())
+}
+
+/// number of differences [`caculate_delta_value`] would find, without building a map
+pub fn changed_count<T: Serialize>(left: &T, right: &T) -> Result<usize, Error> {
+	let left = to_data_limited(left, MAX_DEPTH)?;
+	let right = to_data_limited(right, MAX_DEPTH)?;
+	let mut count = 0;
+	caculate_delta_all(left, right, &mut |_, _| count += 1, &DeltaOptions::default())?;
+	Ok(count)
+}
+
+/// add the output of [`caculate_delta`] to `input`. `f32` fields are rounded back to `f32` every time, see [`DataEnum::Float`]
+pub fn apply_delta<'a, T: Serialize+ Deserialize<'a>>(input: &mut T, delta_map: &HashMap<String, f64>) -> Result<(), Error> {
+	apply_delta_scaled(input, delta_map, 1.0)
+}
+
+/// same as [`apply_delta`], but every delta will be multiplied by `factor` first. integer fields will be rounded and clamped into the range of their type
+pub fn apply_delta_scaled<'a, T: Serialize+ Deserialize<'a>>(input: &mut T, delta_map: &HashMap<String, f64>, factor: f64) -> Result<(), Error> {
+	let delta_map = delta_map.iter().map(|(id, t)| (id.clone(), DeltaValue::Number(*t))).collect();
+	apply_delta_inner(input, &delta_map, factor, NonFinitePolicy::Skip, &HashMap::new(), MAX_DEPTH)
+}
+
+/// same as [`apply_delta`], but with your own depth limit instead of [`MAX_DEPTH`], see [`from_data_with_max_depth`]. large limits can overflow the stack
+pub fn apply_delta_with_max_depth<'a, T: Serialize+ Deserialize<'a>>(input: &mut T, delta_map: &HashMap<String, f64>, max_depth: usize) -> Result<(), Error> {
+	let delta_map = delta_map.iter().map(|(id, t)| (id.clone(), DeltaValue::Number(*t))).collect();
+	apply_delta_inner(input, &delta_map, 1.0, NonFinitePolicy::Skip, &HashMap::new(), max_depth)
+}
+
+/// same as [`apply_delta`], but fields in `clamps` are clamped into their range after the delta is added, using the same ids as the delta map. this works for both float and int fields, and can be tighter than the range of the type.
+/// int fields are clamped to the ints inside the range, so a range without any int in it, like `0.2..=0.8`, leaves them unclamped
+pub fn apply_delta_clamped<'a, T: Serialize+ Deserialize<'a>>(input: &mut T, delta_map: &HashMap<String, f64>, clamps: &HashMap<String, RangeInclusive<f64>>) -> Result<(), Error> {
+	let delta_map = delta_map.iter().map(|(id, t)| (id.clone(), DeltaValue::Number(*t))).collect();
+	apply_delta_inner(input, &delta_map, 1.0, NonFinitePolicy::Skip, clamps, MAX_DEPTH)
+}
+
+/// apply the output of [`caculate_delta_value`], numeric fields will be added and bool and string fields will be replaced.
+/// [`DeltaValue::Added`] replaces the field, [`DeltaValue::Removed`] sets the field to `None` or drops the map entry. map entries missing in `input` can not be added back since their key is not recorded
+pub fn apply_delta_value<'a, T: Serialize+ Deserialize<'a>>(input: &mut T, delta_map: &HashMap<String, DeltaValue>) -> Result<(), Error> {
+	apply_delta_value_with(input, delta_map, NonFinitePolicy::Skip)
+}
+
+/// same as [`apply_delta_value`], but lets you choose what to do with NaN or infinite deltas
+pub fn apply_delta_value_with<'a, T: Serialize+ Deserialize<'a>>(input: &mut T, delta_map: &HashMap<String, DeltaValue>, policy: NonFinitePolicy) -> Result<(), Error> {
+	apply_delta_inner(input, delta_map, 1.0, policy, &HashMap::new(), MAX_DEPTH)
+}
+
+/// blend two values at `t`, `0.0` gives `a` and `1.0` gives `b`. useful for crossfading between two states of the same type.
+/// int fields are rounded and clamped into the range of `b`, float fields are blended linearly, every other leaf takes the value of `b`.
+/// enums with different variants and options where only one side is `None` take `b` as well.
+/// `a` and `b` must have the same shape, otherwise a [`Error::UnexpectedType`] is returned, wrapped in a [`Error::At`] pointing at the mismatched field.
+/// map entries are matched by key rather than by position and only their values are blended, maps with different keys return a [`Error::UnknownField`] with the missing key.
+/// returns [`Error::DepthLimitExceeded`] if `b` is nested deeper than [`MAX_DEPTH`]
+pub fn interpolate(a: &ParsedData, b: &ParsedData, t: f64) -> Result<ParsedData, Error> {
+	interpolate_with_max_depth(a, b, t, MAX_DEPTH)
+}
+
+/// same as [`interpolate`], but with your own depth limit instead of [`MAX_DEPTH`], see [`from_data_with_max_depth`]. large limits can overflow the stack
+pub fn interpolate_with_max_depth(a: &ParsedData, b: &ParsedData, t: f64, max_depth: usize) -> Result<ParsedData, Error> {
+	b.check_depth(max_depth)?;
+	interpolate_inner(a, b, t)
+}
+
+fn interpolate_inner(a: &ParsedData, b: &ParsedData, t: f64) -> Result<ParsedData, Error> {
+	let data = interpolate_data(&a.data, &b.data, t).map_err(|e| e.at(&b.name))?;
+	Ok(ParsedData {
+		data,
+		name: b.name.clone(),
+	})
+}
+
+fn interpolate_data(a: &DataEnum, b: &DataEnum, t: f64) -> Result<DataEnum, Error> {
+	let data = match (a, b) {
+		(DataEnum::Node(ainner), DataEnum::Node(binner)) => {
+			if ainner.len() != binner.len() {
+				return Err(Error::UnexpectedType(format!("node of {} elements", ainner.len()), b.type_name()));
+			}
+			if binner.iter().all(|binside| matches!(binside.data, DataEnum::Map(_))) {
+				// the order of map entries is not stable for maps like `HashMap`, so entries are matched by key
+				DataEnum::Node(binner.iter().map(|binside| interpolate_inner(map_entry(ainner, binside)?, binside, t)).collect::<Result<_, _>>()?)
+			}else {
+				DataEnum::Node(ainner.iter().zip(binner).map(|(ainside, binside)| interpolate_inner(ainside, binside, t)).collect::<Result<_, _>>()?)
+			}
+		},
+		// keys are never blended, the key of `b` is kept as is
+		(DataEnum::Map(abox_inside), DataEnum::Map(bbox_inside)) => {
+			DataEnum::Map(Box::new((bbox_inside.0.clone(), interpolate_inner(&abox_inside.1, &bbox_inside.1, t)?)))
+		},
+		(DataEnum::Enum(avariant, ainner), DataEnum::Enum(bvariant, binner)) if avariant == bvariant && ainner.len() == binner.len() => {
+			DataEnum::Enum(bvariant.clone(), ainner.iter().zip(binner).map(|(ainside, binside)| interpolate_inner(ainside, binside, t)).collect::<Result<_, _>>()?)
+		},
+		(DataEnum::Some(ainside), DataEnum::Some(binside)) => DataEnum::Some(Box::new(interpolate_inner(ainside, binside, t)?)),
+		(DataEnum::Int(avalue, _), DataEnum::Int(bvalue, range)) => {
+			let x = *avalue as f64 + (*bvalue as f64 - *avalue as f64) * t;
+			DataEnum::Int(clamp(x.round() as i128, range), range.clone())
+		},
+		(DataEnum::Float(avalue), DataEnum::Float(bvalue)) => DataEnum::Float(avalue + (bvalue - avalue) * t),
+		(DataEnum::Enum(_, _), DataEnum::Enum(_, _)) |
+		(DataEnum::Some(_) | DataEnum::None, DataEnum::Some(_) | DataEnum::None) |
+		(DataEnum::Data(_), DataEnum::Data(_)) |
+		(DataEnum::String(_), DataEnum::String(_)) |
+		(DataEnum::Bool(_), DataEnum::Bool(_)) => b.clone(),
+		_ => return Err(Error::UnexpectedType(a.type_name().to_string(), b.type_name())),
+	};
+	Ok(data)
+}
+
+/// the ints inside of `range`, or `None` if there are none, like for `0.2..=0.8`. a reversed range is treated as if it was swapped, the same as [`clamp`] does
+fn int_range(range: &RangeInclusive<f64>) -> Option<RangeInclusive<i128>> {
+	let (start, end) = if range.start() <= range.end() {
+		(range.start().ceil(), range.end().floor())
+	}else {
+		(range.end().ceil(), range.start().floor())
+	};
+	(start <= end).then_some(start as i128..=end as i128)
+}
+
+/// the entry of `entries` with the same key as `entry`, returns [`Error::UnknownField`] if there is none
+fn map_entry<'a>(entries: &'a [ParsedData], entry: &ParsedData) -> Result<&'a ParsedData, Error> {
+	let key = match &entry.data {
+		DataEnum::Map(box_inside) => &box_inside.0.data,
+		data => return Err(Error::UnexpectedType("map".to_string(), data.type_name())),
+	};
+	entries.iter().find(|inside| matches!(&inside.data, DataEnum::Map(box_inside) if box_inside.0.data == *key))
+		.ok_or_else(|| Error::UnknownField(entry.name.clone()))
+}
+
+fn apply_delta_inner<'a, T: Serialize+ Deserialize<'a>>(input: &mut T, delta_map: &HashMap<String, DeltaValue>, factor: f64, policy: NonFinitePolicy, clamps: &HashMap<String, RangeInclusive<f64>>, max_depth: usize) -> Result<(), Error> {
+	if delta_map.is_empty() {
+		return Ok(());
+	}
+	let mut data = to_data_limited(input, max_depth)?;
+	apply_delta_data("", &mut data, delta_map, factor, policy, clamps)?;
+	*input = from_data_with_max_depth(&mut data, max_depth)?;
+	Ok(())
+}
+
+/// returns `Ok(true)` if the delta can be used
+fn check_finite(id: &str, delta: f64, policy: NonFinitePolicy) -> Result<bool, Error> {
+	if delta.is_finite() {
+		Ok(true)
+	}else if policy == NonFinitePolicy::Error {
+		Err(Error::NonFinite(id.to_string()))
+	}else {
+		Ok(false)
+	}
+}
+
+fn apply_delta_data(id: &str, data: &mut ParsedData, map: &HashMap<String, DeltaValue>, factor: f64, policy: NonFinitePolicy, clamps: &HashMap<String, RangeInclusive<f64>>) -> Result<(), Error> {
+	let id = join_id(id, &data.name);
+	match map.get(&id) {
+		Some(DeltaValue::Added(value)) => {
+			data.data = value.clone();
+			return Ok(());
+		},
+		Some(DeltaValue::Removed) => {
+			data.data = DataEnum::None;
+			return Ok(());
+		},
+		_ => {}
+	}
+	match &mut data.data {
+		DataEnum::Node(inner) => {
+			inner.retain(|inside| match &inside.data {
+				DataEnum::Map(box_inside) => map.get(&join_id(&join_id(&id, &inside.name), &box_inside.1.name)) != Some(&DeltaValue::Removed),
+				_ => true,
+			});
+			for inside in inner {
+				apply_delta_data(&id, inside, map, factor, policy, clamps)?;
+			}
+		},
+		DataEnum::Map(box_inside) => {
+			apply_delta_data(&id, &mut box_inside.1, map, factor, policy, clamps)?;
+		},
+		DataEnum::Enum(_, inner) => {
+			for inside in inner {
+				apply_delta_data(&id, inside, map, factor, policy, clamps)?;
+			}
+		},
+		DataEnum::Int(value, range) => {
+			if let Some(DeltaValue::Number(t)) = map.get(&id) {
+				let t = *t * factor;
+				if !check_finite(&id, t, policy)? {
+					return Ok(());
+				}
+				*value = clamp((t.round() as i128).saturating_add(*value), range);
+				if let Some(range) = clamps.get(&id).and_then(int_range) {
+					*value = clamp(*value, &range);
+				}
+			}
+		},
+		DataEnum::Float(value) => {
+			if let Some(DeltaValue::Number(t)) = map.get(&id) {
+				let t = *t * factor;
+				if check_finite(&id, t, policy)? {
+					*value += t;
+					if let Some(range) = clamps.get(&id) {
+						*value = clamp(*value, range);
+					}
+				}
+			}
+		},
+		DataEnum::Bool(value) => {
+			if let Some(DeltaValue::Bool(t)) = map.get(&id) {
+				*value = *t;
+			}
+		},
+		DataEnum::String(value) => {
+			if let Some(DeltaValue::String(t)) = map.get(&id) {
+				*value = t.clone();
+			}
+		},
+		_ => {}
+	}
+	Ok(())
+}
+
+/// options of [`caculate_delta_data`], shared by every field
+struct DeltaOptions<'a> {
+	policy: NonFinitePolicy,
+	/// fields whose id ends with one of these are skipped together with everything beneath them
+	ignore: &'a [&'a str],
+	/// floats that differ by no more than this are treated as equal
+	epsilon: f64,
+	/// both sides must not be nested deeper than this
+	max_depth: usize,
+}
+
+impl Default for DeltaOptions<'_> {
+	fn default() -> Self {
+		Self {
+			policy: NonFinitePolicy::Skip,
+			ignore: &[],
+			epsilon: 0.0,
+			max_depth: MAX_DEPTH,
+		}
+	}
+}
+
+/// same as [`caculate_delta_data`] from the root, for `insert`s that never stop the walk
+fn caculate_delta_all(left: ParsedData, right: ParsedData, insert: &mut dyn FnMut(String, DeltaValue), options: &DeltaOptions) -> Result<(), Error> {
+	caculate_delta_data(left, right, &mut |id, value| {
+		insert(id, value);
+		ControlFlow::Continue(())
+	}, String::new(), options).map(|_| ())
+}
+
+/// return early from [`caculate_delta_data`] if `insert` asked to stop
+macro_rules! walk {
+	($flow: expr) => {
+		if $flow.is_break() {
+			return Ok(ControlFlow::Break(()));
+		}
+	};
+}
+
+/// `insert` gets every difference and can break to stop the walk early, which is then returned
+fn caculate_delta_data(left: ParsedData, right: ParsedData, insert: &mut dyn FnMut(String, DeltaValue) -> ControlFlow<()>, id: String, options: &DeltaOptions) -> Result<ControlFlow<()>, Error> {
+	let ignore = options.ignore;
+	let id = join_id(&id, &left.name);
+	if is_ignored(&id, ignore) {
+		return Ok(ControlFlow::Continue(()));
+	}
+	let flow = match (left.data, right.data) {
+		// maps are matched by key, so entries only on one side are reported instead of being zipped with other keys
+		(DataEnum::Node(linner), DataEnum::Node(rinner)) if is_keyed_map(&linner) && is_keyed_map(&rinner) => {
+			let mut rinner: HashMap<String, ParsedData> = rinner.into_iter().map(|data| (data.name.clone(), data)).collect();
+			for linside in linner {
+				match rinner.remove(&linside.name) {
+					Some(rinside) => walk!(caculate_delta_data(linside, rinside, insert, id.clone(), options)?),
+					None if is_ignored(&join_id(&id, &linside.name), ignore) => {},
+					None => {
+						let (id, data) = entry_value(&id, linside);
+						walk!(insert(id, DeltaValue::Added(data)));
+					},
+				}
+			}
+			for rinside in rinner.into_values() {
+				if !is_ignored(&join_id(&id, &rinside.name), ignore) {
+					walk!(insert(entry_value(&id, rinside).0, DeltaValue::Removed));
+				}
+			}
+			ControlFlow::Continue(())
+		},
+		(DataEnum::Node(linner), DataEnum::Node(rinner)) | (DataEnum::Enum(_, linner), DataEnum::Enum(_, rinner)) => {
+			for (linside, rinside) in linner.into_iter().zip(rinner.into_iter()) {
+				walk!(caculate_delta_data(linside, rinside, insert, id.clone(), options)?);
+			}
+			ControlFlow::Continue(())
+		},
+		(DataEnum::Map(lbox_inside), DataEnum::Map(rbox_inside),) => {
+			let ((_, linner), (_, rinner)) = (*lbox_inside, *rbox_inside);
+			caculate_delta_data(linner, rinner, insert, id, options)?
+		},
+		(DataEnum::Int(lvalue, _), DataEnum::Int(rvalue, _)) if lvalue != rvalue => {
+			// subtracting in i128 keeps the exact difference of large values that are close to each other, only a overflowing difference is subtracted as floats
+			let delta = match lvalue.checked_sub(rvalue) {
+				Some(delta) => delta as f64,
+				None => lvalue as f64 - rvalue as f64,
+			};
+			insert(id, DeltaValue::Number(delta))
+		},
+		// NaN is never within epsilon, so it is still reported like with `!=`
+		(DataEnum::Float(lvalue), DataEnum::Float(rvalue)) if lvalue != rvalue && ((lvalue - rvalue).abs() > options.epsilon || (lvalue - rvalue).is_nan()) => {
+			let delta = lvalue - rvalue;
+			if check_finite(&id, delta, options.policy)? {
+				insert(id, DeltaValue::Number(delta))
+			}else {
+				ControlFlow::Continue(())
+			}
+		},
+		(DataEnum::Bool(lvalue), DataEnum::Bool(rvalue)) if lvalue != rvalue => insert(id, DeltaValue::Bool(lvalue)),
+		(DataEnum::String(lvalue), DataEnum::String(rvalue)) if lvalue != rvalue => insert(id, DeltaValue::String(lvalue)),
+		(DataEnum::None, DataEnum::None) => ControlFlow::Continue(()),
+		(DataEnum::None, _) => insert(id, DeltaValue::Removed),
+		(ldata, DataEnum::None) => insert(id, DeltaValue::Added(ldata)),
+		_ => ControlFlow::Continue(()),
+	};
+	Ok(flow)
+}
+
+fn is_ignored(id: &str, ignore: &[&str]) -> bool {
+	ignore.iter().any(|suffix| id.ends_with(suffix))
+}
+
+/// a node of map entries whose keys all have names, see [`crate::ParsedData`]
+fn is_keyed_map(inner: &[ParsedData]) -> bool {
+	inner.iter().all(|data| matches!(data.data, DataEnum::Map(_)) && !data.name.is_empty())
+}
+
+/// id and value of a map entry, as if the delta walked into it
+fn entry_value(id: &str, entry: ParsedData) -> (String, DataEnum) {
+	let id = join_id(id, &entry.name);
+	match entry.data {
+		DataEnum::Map(box_inside) => {
+			let value = box_inside.1;
+			(join_id(&id, &value.name), value.data)
+		},
+		data => (id, data),
+	}
+}
@@ -0,0 +1,29 @@
+//! store [`half::f16`] fields as [`DataEnum::Float`](crate::DataEnum::Float) so they can be animated, needs `half` feature.
+//!
+//! `half` serializes `f16` as its raw bits by default, which ends up as a int. use this module on your fields instead:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Style {
+//!     #[serde(with = "nablo_data::f16_float")]
+//!     alpha: half::f16,
+//! }
+//! ```
+//!
+//! the value is widened to `f32` when serializing, which is lossless. when deserializing the `f32` is narrowed back to the nearest `f16`,
+//! so values made by animations or deltas are rounded to `f16` precision, and values out of the range of `f16` become infinite.
+
+use half::f16;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serializer;
+
+/// serialize a `f16` as `f32`
+pub fn serialize<S: Serializer>(value: &f16, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_f32(value.to_f32())
+}
+
+/// deserialize a `f32` and round it to the nearest `f16`
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f16, D::Error> {
+	f32::deserialize(deserializer).map(f16::from_f32)
+}
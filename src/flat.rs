@@ -0,0 +1,139 @@
+//! turn a [`ParsedData`] into a flat map of ids and back, for storing it in a key value store. needs `std` feature
+
+use crate::join_id;
+use crate::split_id;
+use crate::DataEnum;
+use crate::Error;
+use crate::ParsedData;
+use crate::MAX_DEPTH;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// every leaf of `data` keyed by its id, the same ids as [`ParsedData::leaves`] gives. see [`unflatten`] for the reverse.
+///
+/// enums also get a entry at their own id holding the variant without its fields, map entries get one holding the key and a `None` value, and empty nodes are kept as `Node(vec![])`, so all of them survive [`unflatten`].
+/// children with the same id (like two map keys with the same name) overwrite each other.
+/// returns [`Error::DepthLimitExceeded`] if `data` is nested deeper than [`MAX_DEPTH`]
+pub fn flatten(data: &ParsedData) -> Result<HashMap<String, DataEnum>, Error> {
+	flatten_with_max_depth(data, MAX_DEPTH)
+}
+
+/// same as [`flatten`], but with your own depth limit instead of [`MAX_DEPTH`], see [`from_data_with_max_depth`](crate::from_data_with_max_depth). large limits can overflow the stack
+pub fn flatten_with_max_depth(data: &ParsedData, max_depth: usize) -> Result<HashMap<String, DataEnum>, Error> {
+	data.check_depth(max_depth)?;
+	let mut output = HashMap::new();
+	flatten_into("", data, &mut output);
+	Ok(output)
+}
+
+fn flatten_into(id: &str, data: &ParsedData, output: &mut HashMap<String, DataEnum>) {
+	let id = join_id(id, &data.name);
+	match &data.data {
+		DataEnum::Node(inner) => {
+			if inner.is_empty() {
+				output.insert(id.clone(), DataEnum::Node(vec!()));
+			}
+			for inside in inner {
+				flatten_into(&id, inside, output);
+			}
+		},
+		DataEnum::Enum(variant, inner) => {
+			output.insert(id.clone(), DataEnum::Enum(variant.clone(), vec!()));
+			for inside in inner {
+				flatten_into(&id, inside, output);
+			}
+		},
+		DataEnum::Map(box_inside) => {
+			output.insert(id.clone(), DataEnum::Map(Box::new((box_inside.0.clone(), ParsedData::default()))));
+			flatten_into(&id, &box_inside.1, output);
+		},
+		data => {
+			output.insert(id, data.clone());
+		},
+	}
+}
+
+/// rebuild a [`ParsedData`] from the output of [`flatten`]. missing nodes on the way to a id are created.
+///
+/// a flat map does not keep the order of fields, so children are sorted: elements named by their index come first in order of the index, then the others by name.
+/// returns [`Error::UnknownField`] if a id does not start with `----` or has a different root than the others, [`Error::DepthLimitExceeded`] if a id has more than [`MAX_DEPTH`] segments,
+/// and [`Error::UnexpectedType`] wrapped in a [`Error::At`] if a id is both a leaf and the parent of other ids, or a map entry without exactly one value
+pub fn unflatten(map: &HashMap<String, DataEnum>) -> Result<ParsedData, Error> {
+	unflatten_with_max_depth(map, MAX_DEPTH)
+}
+
+/// same as [`unflatten`], but with your own depth limit instead of [`MAX_DEPTH`], see [`from_data_with_max_depth`](crate::from_data_with_max_depth). large limits can overflow the stack
+pub fn unflatten_with_max_depth(map: &HashMap<String, DataEnum>, max_depth: usize) -> Result<ParsedData, Error> {
+	let mut root: Option<(Cow<str>, FlatNode)> = None;
+	for (id, value) in map {
+		let segments: Vec<Cow<str>> = match split_id(id) {
+			Some(segments) => segments.collect(),
+			None => return Err(Error::UnknownField(id.clone())),
+		};
+		// every segment is one level of the tree, and both building and dropping it recurse
+		if segments.len() > max_depth {
+			return Err(Error::DepthLimitExceeded(max_depth));
+		}
+		let mut segments = segments.into_iter();
+		let name = segments.next().unwrap_or_default();
+		let (root_name, node) = root.get_or_insert_with(|| (name.clone(), FlatNode::default()));
+		if *root_name != name {
+			return Err(Error::UnknownField(id.clone()));
+		}
+		node.insert(segments, value);
+	}
+	match root {
+		Some((name, node)) => node.build(&name, &join_id("", &name)),
+		None => Ok(ParsedData::default()),
+	}
+}
+
+/// a node of the tree [`unflatten`] builds before turning it into [`ParsedData`]
+#[derive(Default)]
+struct FlatNode<'a> {
+	value: Option<&'a DataEnum>,
+	children: Vec<(Cow<'a, str>, FlatNode<'a>)>,
+}
+
+impl<'a> FlatNode<'a> {
+	fn insert(&mut self, mut segments: impl Iterator<Item = Cow<'a, str>>, value: &'a DataEnum) {
+		match segments.next() {
+			None => self.value = Some(value),
+			Some(segment) => {
+				let index = match self.children.iter().position(|(name, _)| *name == segment) {
+					Some(index) => index,
+					None => {
+						self.children.push((segment, FlatNode::default()));
+						self.children.len() - 1
+					},
+				};
+				self.children[index].1.insert(segments, value);
+			},
+		}
+	}
+
+	fn build(mut self, name: &str, id: &str) -> Result<ParsedData, Error> {
+		self.children.sort_by(|(left, _), (right, _)| match (left.parse::<usize>(), right.parse::<usize>()) {
+			(Ok(left), Ok(right)) => left.cmp(&right),
+			(Ok(_), Err(_)) => core::cmp::Ordering::Less,
+			(Err(_), Ok(_)) => core::cmp::Ordering::Greater,
+			(Err(_), Err(_)) => left.cmp(right),
+		});
+		let mut children = Vec::with_capacity(self.children.len());
+		for (name, child) in self.children {
+			children.push(child.build(&name, &join_id(id, &name))?);
+		}
+		let data = match self.value {
+			Some(DataEnum::Enum(variant, _)) => DataEnum::Enum(variant.clone(), children),
+			Some(DataEnum::Map(box_inside)) if children.len() == 1 => DataEnum::Map(Box::new((box_inside.0.clone(), children.remove(0)))),
+			Some(DataEnum::Map(_)) => return Err(Error::At(id.to_string(), Box::new(Error::UnexpectedType(stringify!(map).to_string(), "node")))),
+			Some(DataEnum::Node(_)) | None => DataEnum::Node(children),
+			Some(value) if children.is_empty() => value.clone(),
+			Some(value) => return Err(Error::At(id.to_string(), Box::new(Error::UnexpectedType(stringify!(node).to_string(), value.type_name())))),
+		};
+		Ok(ParsedData {
+			data,
+			name: name.to_string(),
+		})
+	}
+}
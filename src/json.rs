@@ -0,0 +1,108 @@
+//! bridge between [`ParsedData`] and [`serde_json::Value`], needs `json` feature
+
+use crate::DataEnum;
+use crate::ParsedData;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::Map;
+use serde_json::Number;
+use serde_json::Value;
+
+/// turn a [`ParsedData`] into json.
+///
+/// * sequences and tuples, whose children are named by their index, become arrays
+/// * other nodes whose children all have different, non-empty names become objects, the rest become arrays
+/// * map entries inside objects use their key as the name, map entries inside arrays become `[key, value]`
+/// * unit variants become strings, other variants become objects with the variant name as the only key, like `serde_json` does
+/// * the range of [`DataEnum::Int`] is dropped, ints out of `i64` and `u64` are written as floats
+/// * [`DataEnum::Data`] is written as a base64 string
+/// * NaN and infinite floats become `null`
+/// * [`DataEnum::Some`] is written as its inner value, so `Some(None)` becomes `null` as well
+pub fn to_json(data: &ParsedData) -> Value {
+	match &data.data {
+		DataEnum::Node(inner) => node_to_json(inner),
+		DataEnum::Map(box_inside) => Value::Array(vec!(to_json(&box_inside.0), to_json(&box_inside.1))),
+		DataEnum::Enum(variant, inner) => {
+			if inner.is_empty() {
+				return Value::String(variant.clone());
+			}
+			let value = if inner.len() == 1 && inner[0].name.is_empty() {
+				to_json(&inner[0])
+			}else {
+				node_to_json(inner)
+			};
+			let mut map = Map::new();
+			map.insert(variant.clone(), value);
+			Value::Object(map)
+		},
+		DataEnum::Data(inner) => Value::String(STANDARD.encode(inner)),
+		DataEnum::String(inner) => Value::String(inner.clone()),
+		DataEnum::Int(inner, _) => {
+			if let Ok(t) = i64::try_from(*inner) {
+				Value::Number(t.into())
+			}else if let Ok(t) = u64::try_from(*inner) {
+				Value::Number(t.into())
+			}else {
+				float_to_json(&data.data)
+			}
+		},
+		DataEnum::Float(_) => float_to_json(&data.data),
+		DataEnum::Bool(inner) => Value::Bool(*inner),
+		DataEnum::Some(inner) => to_json(inner),
+		DataEnum::None => Value::Null,
+	}
+}
+
+fn float_to_json(data: &DataEnum) -> Value {
+	data.as_f64().and_then(Number::from_f64).map(Value::Number).unwrap_or(Value::Null)
+}
+
+fn node_to_json(inner: &[ParsedData]) -> Value {
+	if inner.iter().enumerate().all(|(index, data)| data.name == index.to_string() && !matches!(data.data, DataEnum::Map(_))) {
+		return Value::Array(inner.iter().map(to_json).collect());
+	}
+	let mut map = Map::new();
+	for data in inner {
+		if data.name.is_empty() || map.contains_key(&data.name) {
+			return Value::Array(inner.iter().map(to_json).collect());
+		}
+		let value = match &data.data {
+			DataEnum::Map(box_inside) => to_json(&box_inside.1),
+			_ => to_json(data),
+		};
+		map.insert(data.name.clone(), value);
+	}
+	Value::Object(map)
+}
+
+/// turn json into a [`ParsedData`].
+///
+/// json does not know the type of your struct, so this is not the reverse of [`to_json`]:
+/// * objects become nodes with named children and arrays become nodes with children named by their index
+/// * integers get the range of `i64` (or `u64` if they are larger than `i64::MAX`), other numbers become floats
+/// * strings always become [`DataEnum::String`], base64 is never decoded
+/// * `null` becomes [`DataEnum::None`]
+pub fn from_json(value: &Value) -> ParsedData {
+	match value {
+		Value::Null => ParsedData::default(),
+		Value::Bool(inner) => (*inner).into(),
+		Value::Number(inner) => {
+			if let Some(t) = inner.as_i64() {
+				t.into()
+			}else if let Some(t) = inner.as_u64() {
+				t.into()
+			}else {
+				inner.as_f64().unwrap_or(f64::NAN).into()
+			}
+		},
+		Value::String(inner) => inner.as_str().into(),
+		Value::Array(inner) => inner.iter().enumerate().map(|(index, value)| ParsedData {
+			name: index.to_string(),
+			..from_json(value)
+		}).collect::<Vec<ParsedData>>().into(),
+		Value::Object(inner) => inner.iter().map(|(name, value)| ParsedData {
+			name: name.clone(),
+			..from_json(value)
+		}).collect::<Vec<ParsedData>>().into(),
+	}
+}
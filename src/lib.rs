@@ -1,15 +1,61 @@
 //! data processing functions for `nablo`
-
-use std::ops::RangeInclusive;
-use nablo_shape::prelude::Animation;
-use time::Duration;
-use std::collections::HashMap;
+//!
+//! the `std` feature is on by default and brings animations and deltas, which need `HashMap`.
+//! without it this crate is `no_std` and only needs `alloc`, [`ParsedData`], [`to_data`], [`from_data`] and the binary format still work.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::format;
+#[cfg(feature = "profile")]
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "profile")]
+use core::cell::Cell;
+use core::ops::RangeInclusive;
 use serde::de::*;
 use serde::Deserializer;
-use std::fmt::Display;
+use core::fmt::Display;
 use serde::Serialize;
 use serde::ser;
 
+#[cfg(feature = "std")]
+mod animation;
+#[cfg(feature = "std")]
+pub use animation::*;
+/// implements [`CanBeAnimated`](trait@CanBeAnimated) for a struct with exactly one field marked `#[animate]` (the target) and one marked `#[animations]` (its animation map)
+#[cfg(feature = "derive")]
+pub use nablo_data_derive::CanBeAnimated;
+mod binary;
+pub use binary::*;
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "cbor")]
+pub use cbor::*;
+#[cfg(feature = "std")]
+mod delta;
+#[cfg(feature = "std")]
+pub use delta::*;
+#[cfg(feature = "half")]
+pub mod f16_float;
+#[cfg(feature = "std")]
+mod flat;
+#[cfg(feature = "std")]
+pub use flat::*;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "std")]
+pub mod time_float;
+#[cfg(feature = "json")]
+pub use json::*;
+
 /// a enum that represent a value. tuple, array, struct will be parse as Node.
 #[derive(PartialEq, Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
 pub enum DataEnum {
@@ -20,22 +66,31 @@ pub enum DataEnum {
 	Enum(String, Vec<ParsedData>),
 	Data(Vec<u8>),
 	String(String),
-	/// contains the range of original value
+	/// contains the range of original value.
+	///
+	/// the range comes from the rust type that was serialized (like `0..=255` for `u8`) rather than the stored value, so serializing the same type always gives the same range, even after a [`from_data`] and [`to_data`] round trip.
+	/// deserializing into a different type and serializing it again gives the range of the new type. `u128` values are stored with range `0..=i128::MAX`.
+	/// the range should start with the smaller bound, but a reversed range is treated as if it was swapped when clamping.
+	/// deserializing a [`DataEnum`] with serde or [`from_bytes`] fails if the range is reversed or does not contain the value
+	#[serde(deserialize_with = "deserialize_int")]
 	Int(i128, RangeInclusive<i128>),
+	/// `f32` values are widened without loss and rounded to the nearest `f32` when deserialized, so animations and deltas on `f32` fields are computed in `f64` and rounded once per [`from_data`].
+	/// repeatedly applying a delta drifts no more than adding it in `f32` every time would, at most half a `f32` step per application
 	Float(f64),
 	Bool(bool),
+	/// a `Some` whose value is [`DataEnum::None`] or another [`DataEnum::Some`], so nested options like `Some(None)` will not collapse into `None`. other `Some` values are stored directly
+	Some(Box<ParsedData>),
+	/// `None`, `()` and unit structs. since `()` is stored as `None`, `Some(())` is stored as a [`DataEnum::Some`] around it and stays apart from `None`
 	#[default] None,
 }
 
-/// a struct that represent a struct, see more in [`DataEnum`]. Note: if a map's key is not one of string int float or bool, nablo will not deliver name field
+/// a struct that represent a struct, see more in [`DataEnum`]. Note: if a map's key is not one of string int float or bool, nablo will not deliver name field, but the whole key is still stored in [`DataEnum::Map`]
 #[derive(PartialEq, Debug, Clone, serde::Serialize, serde::Deserialize, Default)]
 pub struct ParsedData {
 	/// the value
 	pub data: DataEnum,
 	/// name of the value
 	pub name: String,
-	#[serde(skip)]
-	need_delete: bool
 }
 
 /// all possible errors when parsing data.
@@ -44,11 +99,35 @@ pub enum Error {
 	/// error during serializing or deserializing
 	#[error("error during serializing or deserializing... info: {0}")]
 	SerdeError(String),
-	/// unexpected type, contains expected type name
-	#[error("error while deserializing elements, info: unexpected type, expect: {0}")]
-	UnexpectedType(String),
+	/// unexpected type, contains expected type name and the [`DataEnum::type_name`] that was found
+	#[error("error while deserializing elements, info: unexpected type, expect: {0}, found: {1}")]
+	UnexpectedType(String, &'static str),
 	#[error("syntax error")]
-	Syntax
+	Syntax,
+	/// a field that the target type does not have, see [`from_data_strict`]. also returned by [`interpolate`] for a map key that only one side has
+	#[error("unknown field: {0}")]
+	UnknownField(String),
+	/// a int that does not fit in the target type, contains the value and the target type. ints are never wrapped, so a `u64` above `i64::MAX` read as a `i64` ends up here
+	#[error("value {0} does not fit in {1}")]
+	Overflow(i128, &'static str),
+	/// a enum variant that the target enum does not have, contains the stored variant and the variants of the target enum
+	#[error("unknown variant: {0}, expect one of: {1:?}")]
+	UnknownVariant(String, &'static [&'static str]),
+	/// two keys of a map have the same name, see [`to_data_strict`]
+	#[error("duplicate map key: {0}")]
+	DuplicateKey(String),
+	/// a error while serializing a field, contains the id of the field (see [`field_id`]) and the error
+	#[error("at {0}: {1}")]
+	At(String, Box<Error>),
+	/// a value nested deeper than the limit, contains the limit, see [`MAX_DEPTH`]
+	#[error("nesting deeper than {0}")]
+	DepthLimitExceeded(usize),
+	/// encoded bytes longer than the limit, contains the limit, see [`to_bytes_bounded`]. a single length or count over `u32::MAX` can not be encoded at all and reports `u32::MAX`
+	#[error("encoded size larger than {0} bytes")]
+	SizeLimitExceeded(usize),
+	/// a NaN or infinite delta, contains the id of the field, see [`NonFinitePolicy`]
+	#[error("non-finite delta in field: {0}")]
+	NonFinite(String),
 }
 
 impl serde::ser::Error for Error {
@@ -61,84 +140,845 @@ impl serde::de::Error for Error {
 	fn custom<T: Display>(input: T) -> Self {
 		Self::SerdeError(input.to_string())
 	}
+
+	fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+		Self::UnknownVariant(variant.to_string(), expected)
+	}
+}
+
+impl Error {
+	/// mark the error as coming from the child `name`
+	fn at(self, name: &str) -> Self {
+		match self {
+			Error::At(path, inner) => Error::At(join_id("", name) + &path, inner),
+			error => Error::At(join_id("", name), Box::new(error)),
+		}
+	}
+}
+
+/// `true` if `range` is not reversed and contains `value`
+fn is_valid_int(value: i128, range: &RangeInclusive<i128>) -> bool {
+	range.start() <= range.end() && range.contains(&value)
+}
+
+fn deserialize_int<'de, D: Deserializer<'de>>(input: D) -> Result<(i128, RangeInclusive<i128>), D::Error> {
+	let (value, range) = <(i128, RangeInclusive<i128>)>::deserialize(input)?;
+	if is_valid_int(value, &range) {
+		Ok((value, range))
+	}else {
+		Err(<D::Error as serde::de::Error>::custom(format!("int {} is out of its range {:?}", value, range)))
+	}
+}
+
+/// short text for logs and labels. leaves are written as their value (ints without their range, strings without quotes), containers as a summary like `Node(3 fields)`.
+/// use [`to_pretty_string`] to see a whole tree
+impl Display for DataEnum {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			DataEnum::Node(inner) => write!(f, "Node({} fields)", inner.len()),
+			DataEnum::Map(box_inside) => write!(f, "Map({})", box_inside.0.data),
+			DataEnum::Enum(variant, inner) => if inner.is_empty() {
+				write!(f, "{}", variant)
+			}else {
+				write!(f, "{}({} fields)", variant, inner.len())
+			},
+			DataEnum::Data(inner) => write!(f, "Data({} bytes)", inner.len()),
+			DataEnum::String(inner) => write!(f, "{}", inner),
+			DataEnum::Int(inner, _) => write!(f, "{}", inner),
+			DataEnum::Float(inner) => write!(f, "{:?}", inner),
+			DataEnum::Bool(inner) => write!(f, "{}", inner),
+			DataEnum::Some(inner) => write!(f, "{}", inner.data),
+			DataEnum::None => write!(f, "None"),
+		}
+	}
+}
+
+impl DataEnum {
+	/// name of the variant, used for diagnostics
+	pub fn type_name(&self) -> &'static str {
+		match self {
+			DataEnum::Node(_) => "node",
+			DataEnum::Map(_) => "map",
+			DataEnum::Enum(_, _) => "enum",
+			DataEnum::Data(_) => "data",
+			DataEnum::String(_) => "string",
+			DataEnum::Int(_, _) => "int",
+			DataEnum::Float(_) => "float",
+			DataEnum::Bool(_) => "bool",
+			DataEnum::Some(_) => "some",
+			DataEnum::None => "none",
+		}
+	}
+
+	/// the value of `Int`, `Float` and `Bool` (as `0.0` or `1.0`) as `f64`, `None` for other variants. ints out of the range of `f64` lose precision
+	pub fn as_f64(&self) -> Option<f64> {
+		match self {
+			DataEnum::Int(inner, _) => Some(*inner as f64),
+			DataEnum::Float(inner) => Some(*inner),
+			DataEnum::Bool(inner) => Some(if *inner { 1.0 } else { 0.0 }),
+			_ => None,
+		}
+	}
+}
+
+impl ParsedData {
+	/// a value with a name, like a field of a struct. `data` can be anything that converts into [`ParsedData`], its name is replaced by `name`
+	pub fn named(name: impl Into<String>, data: impl Into<ParsedData>) -> Self {
+		ParsedData {
+			name: name.into(),
+			..data.into()
+		}
+	}
+
+	/// a unnamed [`DataEnum::Int`] with the range of `T`, the same as [`to_data`] makes, so `ParsedData::int::<u8>(5)` is clamped into `0..=255` by animations and deltas
+	pub fn int<T: IntType>(value: T) -> Self {
+		ParsedData {
+			data: DataEnum::Int(value.into(), T::range()),
+			name: String::new(),
+		}
+	}
+
+	/// a unnamed [`DataEnum::Node`], use [`ParsedData::named`] to name it after a struct
+	pub fn node(children: Vec<ParsedData>) -> Self {
+		children.into()
+	}
+
+	/// a entry of a map, put entries in a [`ParsedData::node`] to make the whole map. the entry is named after `key` the same way [`to_data`] does
+	pub fn map(key: impl Into<ParsedData>, value: impl Into<ParsedData>) -> Self {
+		let key = ParsedData {
+			name: String::new(),
+			..key.into()
+		};
+		ParsedData {
+			name: key_name(&key.data),
+			data: DataEnum::Map(Box::new((key, ParsedData {
+				name: String::new(),
+				..value.into()
+			}))),
+		}
+	}
+
+	/// find a immediate child of a [`DataEnum::Node`] by its name. for map entries, the value side will be returned
+	pub fn get(&self, name: &str) -> Option<&ParsedData> {
+		if let DataEnum::Node(inner) = &self.data {
+			inner.iter().find(|data| data.name == name).map(|data| match &data.data {
+				DataEnum::Map(box_inside) => &box_inside.1,
+				_ => data,
+			})
+		}else {
+			None
+		}
+	}
+
+	/// remove a immediate child of a [`DataEnum::Node`] by its name and return it without cloning. for map entries, the whole entry is removed and the value side will be returned
+	pub fn take_child(&mut self, name: &str) -> Option<ParsedData> {
+		if let DataEnum::Node(inner) = &mut self.data {
+			let index = inner.iter().position(|data| data.name == name)?;
+			let data = inner.remove(index);
+			Some(match data.data {
+				DataEnum::Map(box_inside) => box_inside.1,
+				_ => data,
+			})
+		}else {
+			None
+		}
+	}
+
+	/// find a nested value by a path like `a.b.0.c`.
+	///
+	/// the path is made of segments separated by `.`, each segment is matched against the `name` of the children in a [`DataEnum::Node`] or [`DataEnum::Enum`].
+	/// if no child has that name and the segment is a number, it will be used as the index of the child instead. map entries are stepped through to their value side.
+	/// empty paths or paths containing empty segments (like `a..b` or `a.`) returns `None`
+	pub fn get_path(&self, path: &str) -> Option<&ParsedData> {
+		let mut current = self;
+		for segment in path.split('.') {
+			if segment.is_empty() {
+				return None;
+			}
+			current = current.child(segment)?;
+		}
+		Some(current)
+	}
+
+	/// same as [`ParsedData::get_path`], but takes a JSON Pointer (RFC 6901) like `/a/b/0/c`, where `~1` stands for `/` and `~0` for `~` in names.
+	/// the empty pointer is the value itself, pointers not starting with `/` returns `None`
+	pub fn get_pointer(&self, pointer: &str) -> Option<&ParsedData> {
+		if pointer.is_empty() {
+			return Some(self);
+		}
+		let mut current = self;
+		for segment in pointer.strip_prefix('/')?.split('/') {
+			// `~01` is `~1`, so `~1` has to be replaced first
+			current = current.child(&segment.replace("~1", "/").replace("~0", "~"))?;
+		}
+		Some(current)
+	}
+
+	/// replace the value of the leaf at `path`, see [`ParsedData::get_path`] for the format of `path`.
+	/// returns [`Error::UnknownField`] if the path does not exist and [`Error::UnexpectedType`] if it points at a [`DataEnum::Node`], [`DataEnum::Map`] or [`DataEnum::Enum`]
+	pub fn set_path(&mut self, path: &str, value: DataEnum) -> Result<(), Error> {
+		let current = self.path_mut(path)?;
+		match current.data {
+			DataEnum::Node(_) | DataEnum::Map(_) | DataEnum::Enum(_, _) => Err(Error::UnexpectedType(stringify!(leaf).to_string(), current.data.type_name())),
+			_ => {
+				current.data = value;
+				Ok(())
+			}
+		}
+	}
+
+	/// mutable version of [`ParsedData::get_path`], returns [`Error::UnknownField`] if the path does not exist
+	fn path_mut(&mut self, path: &str) -> Result<&mut ParsedData, Error> {
+		let mut current = self;
+		for segment in path.split('.') {
+			if segment.is_empty() {
+				return Err(Error::UnknownField(path.to_string()));
+			}
+			current = match current.child_mut(segment) {
+				Some(inner) => inner,
+				None => return Err(Error::UnknownField(path.to_string())),
+			};
+		}
+		Ok(current)
+	}
+
+	/// change the name of the field at `path`, see [`ParsedData::get_path`] for the format of `path`. for map entries, the key is changed as well.
+	/// returns [`Error::UnknownField`] if the path does not exist, [`Error::DuplicateKey`] if a sibling already has the new name and [`Error::UnexpectedType`] if it is a map entry whose key is not a string
+	pub fn rename(&mut self, path: &str, new_name: &str) -> Result<(), Error> {
+		let (parent, last) = match path.rsplit_once('.') {
+			Some((parent, last)) => (Some(parent), last),
+			None => (None, path),
+		};
+		if last.is_empty() {
+			return Err(Error::UnknownField(path.to_string()));
+		}
+		let mut current = self;
+		for segment in parent.into_iter().flat_map(|parent| parent.split('.')) {
+			current = match current.child_mut(segment) {
+				Some(inner) if !segment.is_empty() => inner,
+				_ => return Err(Error::UnknownField(path.to_string())),
+			};
+		}
+		let inner = match &mut current.data {
+			DataEnum::Node(inner) | DataEnum::Enum(_, inner) => inner,
+			_ => return Err(Error::UnknownField(path.to_string())),
+		};
+		let index = match inner.iter().position(|data| data.name == last) {
+			Some(index) => index,
+			None => last.parse::<usize>().ok().filter(|index| *index < inner.len()).ok_or_else(|| Error::UnknownField(path.to_string()))?,
+		};
+		if inner.iter().enumerate().any(|(i, data)| i != index && data.name == new_name) {
+			return Err(Error::DuplicateKey(new_name.to_string()));
+		}
+		let data = &mut inner[index];
+		if let DataEnum::Map(box_inside) = &mut data.data {
+			match &mut box_inside.0.data {
+				DataEnum::String(key) => *key = new_name.to_string(),
+				key => return Err(Error::UnexpectedType(stringify!(string).to_string(), key.type_name())),
+			}
+		}
+		data.name = new_name.to_string();
+		Ok(())
+	}
+
+	/// replace the value at `path` (a path of [`Error::At`]) with the value at the same path in `default`, or remove it if `default` does not have it. returns `false` if `path` does not exist
+	fn replace_with_default(&mut self, path: &str, default: &ParsedData) -> bool {
+		let segments: Vec<Cow<str>> = match split_id(path) {
+			Some(segments) => segments.collect(),
+			None if path.is_empty() => vec!(),
+			None => return false,
+		};
+		let (last, parents) = match segments.split_last() {
+			Some(inner) => inner,
+			None => {
+				self.data = default.data.clone();
+				return true;
+			},
+		};
+		let mut current = self;
+		let mut default = Some(default);
+		for segment in parents {
+			current = match current.child_mut(segment) {
+				Some(inner) => inner,
+				None => return false,
+			};
+			default = default.and_then(|inner| inner.child(segment));
+		}
+		let inner = match &mut current.data {
+			DataEnum::Node(inner) | DataEnum::Enum(_, inner) => inner,
+			_ => return false,
+		};
+		let index = match inner.iter().position(|data| data.name == *last) {
+			Some(index) => index,
+			None => match last.parse::<usize>() {
+				Ok(index) if index < inner.len() => index,
+				_ => return false,
+			},
+		};
+		match default.and_then(|inner| inner.child(last)) {
+			Some(replacement) => match &mut inner[index].data {
+				DataEnum::Map(box_inside) => box_inside.1.data = replacement.data.clone(),
+				data => *data = replacement.data.clone(),
+			},
+			None => {
+				inner.remove(index);
+			},
+		}
+		true
+	}
+
+	fn child_mut(&mut self, segment: &str) -> Option<&mut ParsedData> {
+		let inner = match &mut self.data {
+			DataEnum::Node(inner) | DataEnum::Enum(_, inner) => inner,
+			_ => return None,
+		};
+		let index = match inner.iter().position(|data| data.name == segment) {
+			Some(index) => index,
+			None => segment.parse::<usize>().ok()?,
+		};
+		let data = inner.get_mut(index)?;
+		Some(match data.data {
+			DataEnum::Map(ref mut box_inside) => &mut box_inside.1,
+			_ => data,
+		})
+	}
+
+	fn child(&self, segment: &str) -> Option<&ParsedData> {
+		let inner = match &self.data {
+			DataEnum::Node(inner) | DataEnum::Enum(_, inner) => inner,
+			_ => return None,
+		};
+		let data = inner.iter().find(|data| data.name == segment).or_else(|| inner.get(segment.parse::<usize>().ok()?))?;
+		Some(match &data.data {
+			DataEnum::Map(box_inside) => &box_inside.1,
+			_ => data,
+		})
+	}
+
+	/// recursively remove children of [`DataEnum::Node`] that are [`DataEnum::None`], including map entries whose value is `None`.
+	/// a node whose children were all removed stays as a empty node rather than becoming `None`, so the shape of the tree above it is kept. children of enums are left untouched since they are positional
+	pub fn prune_none(&mut self) {
+		match &mut self.data {
+			DataEnum::Node(inner) => {
+				inner.retain(|data| match &data.data {
+					DataEnum::None => false,
+					DataEnum::Map(box_inside) => box_inside.1.data != DataEnum::None,
+					_ => true,
+				});
+				for inside in inner {
+					inside.prune_none();
+				}
+			},
+			DataEnum::Map(box_inside) => box_inside.1.prune_none(),
+			DataEnum::Enum(_, inner) => {
+				for inside in inner {
+					inside.prune_none();
+				}
+			},
+			DataEnum::Some(inner) => inner.prune_none(),
+			_ => {}
+		}
+	}
+
+	/// every leaf (values that are not [`DataEnum::Node`], [`DataEnum::Map`] or [`DataEnum::Enum`]) with its id, the id is the same as the one used by animations and deltas, see [`field_id`]
+	pub fn leaves(&self) -> impl Iterator<Item = (String, &DataEnum)> {
+		let mut output = vec!();
+		self.collect_leaves("", &mut output);
+		output.into_iter()
+	}
+
+	fn collect_leaves<'a>(&'a self, id: &str, output: &mut Vec<(String, &'a DataEnum)>) {
+		let id = join_id(id, &self.name);
+		match &self.data {
+			DataEnum::Node(inner) | DataEnum::Enum(_, inner) => {
+				for inside in inner {
+					inside.collect_leaves(&id, output);
+				}
+			},
+			DataEnum::Map(box_inside) => box_inside.1.collect_leaves(&id, output),
+			data => output.push((id, data)),
+		}
+	}
+
+	/// call `f` on every value with its id (see [`field_id`]), parents before their children, so `f` can rewrite nodes as well as leaves.
+	/// children are visited after `f` returns, so a replaced node is walked with its new children. map entries step into their value side without visiting the key,
+	/// and the insides of [`DataEnum::Some`] are not visited, the same as [`ParsedData::leaves`]
+	pub fn walk_mut(&mut self, f: &mut impl FnMut(&str, &mut DataEnum)) {
+		self.walk_mut_inner("", f);
+	}
+
+	fn walk_mut_inner(&mut self, id: &str, f: &mut impl FnMut(&str, &mut DataEnum)) {
+		let id = join_id(id, &self.name);
+		f(&id, &mut self.data);
+		match &mut self.data {
+			DataEnum::Node(inner) | DataEnum::Enum(_, inner) => {
+				for inside in inner {
+					inside.walk_mut_inner(&id, f);
+				}
+			},
+			DataEnum::Map(box_inside) => box_inside.1.walk_mut_inner(&id, f),
+			_ => {}
+		}
+	}
+
+	/// number of leaves, the same ones as [`ParsedData::leaves`] gives
+	pub fn leaf_count(&self) -> usize {
+		match &self.data {
+			DataEnum::Node(inner) | DataEnum::Enum(_, inner) => inner.iter().map(ParsedData::leaf_count).sum(),
+			DataEnum::Map(box_inside) => box_inside.1.leaf_count(),
+			_ => 1,
+		}
+	}
+
+	/// how deeply the value is nested, a leaf is `1` and every [`DataEnum::Node`], [`DataEnum::Map`], [`DataEnum::Enum`] and [`DataEnum::Some`] around it adds `1`, map keys included.
+	/// walks the tree without recursion, so it is safe to call on untrusted input before [`from_data`]
+	pub fn depth(&self) -> usize {
+		let mut max = 0;
+		let mut stack = vec!((self, 1));
+		while let Some((data, depth)) = stack.pop() {
+			max = max.max(depth);
+			match &data.data {
+				DataEnum::Node(inner) | DataEnum::Enum(_, inner) => stack.extend(inner.iter().map(|inside| (inside, depth + 1))),
+				DataEnum::Map(box_inside) => {
+					stack.push((&box_inside.0, depth + 1));
+					stack.push((&box_inside.1, depth + 1));
+				},
+				DataEnum::Some(inside) => stack.push((inside, depth + 1)),
+				_ => {}
+			}
+		}
+		max
+	}
+
+	/// returns [`Error::DepthLimitExceeded`] if [`ParsedData::depth`] is larger than `max_depth`
+	pub fn check_depth(&self, max_depth: usize) -> Result<(), Error> {
+		if self.depth() > max_depth {
+			Err(Error::DepthLimitExceeded(max_depth))
+		}else {
+			Ok(())
+		}
+	}
+
+	/// same as `==`, but children of a [`DataEnum::Node`] or [`DataEnum::Enum`] are matched by their name in any order, so maps serialized from a `HashMap` compare equal whatever their iteration order was.
+	/// children with the same name, like map entries whose key is not named, are matched with each other in any order too
+	pub fn eq_unordered(&self, other: &ParsedData) -> bool {
+		if self.name != other.name {
+			return false;
+		}
+		match (&self.data, &other.data) {
+			(DataEnum::Node(left), DataEnum::Node(right)) => children_eq_unordered(left, right),
+			(DataEnum::Enum(left_variant, left), DataEnum::Enum(right_variant, right)) => left_variant == right_variant && children_eq_unordered(left, right),
+			(DataEnum::Map(left), DataEnum::Map(right)) => left.0.eq_unordered(&right.0) && left.1.eq_unordered(&right.1),
+			(DataEnum::Some(left), DataEnum::Some(right)) => left.eq_unordered(right),
+			(left, right) => left == right,
+		}
+	}
+
+	/// mutable version of [`ParsedData::get`]
+	pub fn get_mut(&mut self, name: &str) -> Option<&mut ParsedData> {
+		if let DataEnum::Node(inner) = &mut self.data {
+			inner.iter_mut().find(|data| data.name == name).map(|data| match data.data {
+				DataEnum::Map(ref mut box_inside) => &mut box_inside.1,
+				_ => data,
+			})
+		}else {
+			None
+		}
+	}
 }
 
-struct Parser {}
+fn children_eq_unordered(left: &[ParsedData], right: &[ParsedData]) -> bool {
+	if left.len() != right.len() {
+		return false;
+	}
+	let mut used = vec!(false; right.len());
+	left.iter().all(|left| {
+		match right.iter().enumerate().position(|(index, right)| !used[index] && right.name == left.name && left.eq_unordered(right)) {
+			Some(index) => {
+				used[index] = true;
+				true
+			},
+			None => false,
+		}
+	})
+}
+
+#[derive(Clone)]
+struct Parser {
+	/// return [`Error::DuplicateKey`] instead of keeping both entries
+	strict: bool,
+	/// store the range of the rust type in [`DataEnum::Int`], or the full range of `i128` if `false`
+	ranges: bool,
+	/// serializing the root value, whose name starts the path of [`Error::At`]
+	root: bool,
+	/// serializing a map key, which stores `u128` values above `i128::MAX` as [`DataEnum::String`] instead of failing
+	key: bool,
+	/// elements to reserve for sequences and maps that do not tell serde their length, see [`to_data_with_capacity`]
+	capacity: usize,
+	/// shared by every parser of the same [`to_data_profiled`] call
+	#[cfg(feature = "profile")]
+	profile: Rc<Cell<Profile>>,
+}
 
 struct DeParser<'a> {
-	data: &'a mut ParsedData
+	data: &'a mut ParsedData,
+	/// return [`Error::UnknownField`] instead of ignoring fields
+	strict: bool,
+	/// wrap errors of fields and elements in [`Error::At`], see [`from_data_collect`]
+	paths: bool,
 }
 
-#[derive(Default)]
 struct Layer {
 	inner: Vec<ParsedData>,
 	final_name: String,
+	/// the serializer of elements
+	parser: Parser,
+	/// names of map keys seen so far, only filled when strict
+	keys: BTreeSet<String>,
+	/// a map key was serialized and is waiting for its value
+	key_pending: bool,
 }
 
-struct DeLayer<'a> {
-	inner: DeParser<'a>,
+struct DeLayer {
+	/// the elements left, taken from the front so sequences and tuples keep their order
+	elements: vec::IntoIter<ParsedData>,
+	strict: bool,
+	paths: bool,
 }
 
-struct DeMap<'a> {
-	inner: DeParser<'a>,
-	temp: Option<ParsedData>
+struct DeMap {
+	/// the entries left, taken from the front like [`DeLayer`]
+	entries: vec::IntoIter<ParsedData>,
+	strict: bool,
+	paths: bool,
+	/// the name and value of the entry whose key was just visited
+	temp: Option<(String, ParsedData)>
 }
 
 struct DeEnum<'a> {
 	inner: &'a mut DeParser<'a>,
 }
 
-impl<'a> DeMap<'a> {
-	fn from(inner: &'a mut ParsedData) -> Self {
+impl DeMap {
+	fn from(inner: Vec<ParsedData>, strict: bool, paths: bool) -> Self {
 		Self {
-			inner: DeParser { data: inner },
+			entries: inner.into_iter(),
+			strict,
+			paths,
 			temp: None
 		}
 	}
 }
 
-impl<'a> DeLayer<'a> {
-	fn from(inner: &'a mut ParsedData) -> Self {
+impl DeLayer {
+	fn from(inner: Vec<ParsedData>, strict: bool, paths: bool) -> Self {
 		Self {
-			inner: DeParser { data: inner }
+			elements: inner.into_iter(),
+			strict,
+			paths,
 		}
 	}
 }
 
 impl Layer {
-	fn new(final_name: impl Into<String>) -> Self {
+	/// serialize a element named `name`, errors are marked with the path of the element
+	fn element<T: ?Sized + Serialize>(&self, name: &str, value: &T) -> Result<ParsedData, Error> {
+		value.serialize(&mut Parser { root: false, key: false, ..self.parser.clone() }).map_err(|error| self.error_at(name, error))
+	}
+
+	/// serialize a map key, see [`Parser::key`]
+	fn key<T: ?Sized + Serialize>(&self, value: &T) -> Result<ParsedData, Error> {
+		value.serialize(&mut Parser { root: false, key: true, ..self.parser.clone() }).map_err(|error| self.error_at("", error))
+	}
+
+	fn error_at(&self, name: &str, error: Error) -> Error {
+		let error = error.at(name);
+		if self.parser.root {
+			error.at(&self.final_name)
+		}else {
+			error
+		}
+	}
+
+	/// `capacity` is the number of elements if serde knows it, so `inner` does not need to grow while serializing
+	fn new(final_name: impl Into<String>, capacity: usize, parser: Parser) -> Self {
 		Self {
+			inner: Vec::with_capacity(capacity),
 			final_name: final_name.into(),
-			..Default::default()
+			parser,
+			keys: BTreeSet::new(),
+			key_pending: false,
 		}
 	}
 }
 
+impl Parser {
+	fn new(strict: bool, ranges: bool) -> Self {
+		Self {
+			strict,
+			ranges,
+			root: true,
+			key: false,
+			capacity: 0,
+			#[cfg(feature = "profile")]
+			profile: Rc::default(),
+		}
+	}
+
+	fn ranged(&self, mut data: ParsedData) -> ParsedData {
+		if let DataEnum::Int(_, range) = &mut data.data {
+			if !self.ranges {
+				*range = i128::MIN..=i128::MAX;
+			}
+		}
+		self.counted(data)
+	}
+
+	/// count `data` in the [`Profile`] of this parser, only the value itself is counted as its children are counted when they are made
+	fn counted(&self, data: ParsedData) -> ParsedData {
+		#[cfg(feature = "profile")]
+		{
+			let mut profile = self.profile.get();
+			match data.data {
+				DataEnum::Node(_) | DataEnum::Map(_) | DataEnum::Enum(_, _) | DataEnum::Some(_) => profile.nodes += 1,
+				_ => profile.leaves += 1,
+			}
+			self.profile.set(profile);
+		}
+		data
+	}
+}
+
+/// how deeply a [`ParsedData`] can be nested before [`from_data`], animations, deltas, [`interpolate`] and [`flatten`] give up with [`Error::DepthLimitExceeded`] instead of overflowing the stack, see [`ParsedData::depth`].
+/// use [`from_data_with_max_depth`], [`CanBeAnimated::get_max_depth`], [`caculate_delta_with_max_depth`], [`apply_delta_with_max_depth`], [`interpolate_with_max_depth`], [`flatten_with_max_depth`] and [`unflatten_with_max_depth`] for a different limit
+pub const MAX_DEPTH: usize = 128;
+
 /// parse a data into [`ParsedData`]
 pub fn to_data<T: serde::Serialize>(input: &T) -> Result<ParsedData, Error> {
-	let mut serializer = Parser {};
+	let mut serializer = Parser::new(false, true);
+	input.serialize(&mut serializer)
+}
+
+/// same as [`to_data`], but every [`DataEnum::Int`] gets the full range of `i128` instead of the range of its rust type, which makes [`to_bytes`] smaller.
+/// the ranges are only used for clamping, so animations and deltas of ints made by this can go out of the range of their type, which fails when deserializing
+pub fn to_data_without_ranges<T: serde::Serialize>(input: &T) -> Result<ParsedData, Error> {
+	let mut serializer = Parser::new(false, false);
+	input.serialize(&mut serializer)
+}
+
+/// same as [`to_data`], but returns [`Error::DuplicateKey`] if a map has two keys with the same name, instead of keeping both entries.
+/// only keys that get a name (strings, ints, floats and bools) are checked
+pub fn to_data_strict<T: serde::Serialize>(input: &T) -> Result<ParsedData, Error> {
+	let mut serializer = Parser::new(true, true);
 	input.serialize(&mut serializer)
 }
 
-/// parse a [`ParsedData`] data into your type
+/// same as [`to_data`], but sequences and maps that do not tell serde their length reserve room for `capacity` elements up front, so they do not grow while serializing.
+/// values whose length is known always reserve exactly that length
+pub fn to_data_with_capacity<T: serde::Serialize>(input: &T, capacity: usize) -> Result<ParsedData, Error> {
+	let mut serializer = Parser { capacity, ..Parser::new(false, true) };
+	input.serialize(&mut serializer)
+}
+
+/// how many values a [`to_data_profiled`] call made, useful to size preallocations and to find types that make much more values than expected
+#[cfg(feature = "profile")]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct Profile {
+	/// every [`DataEnum::Node`], [`DataEnum::Map`], [`DataEnum::Enum`] and [`DataEnum::Some`], map entries included
+	pub nodes: usize,
+	/// every other value, map keys included
+	pub leaves: usize,
+}
+
+/// same as [`to_data`], but also counts every value the serializer made
+#[cfg(feature = "profile")]
+pub fn to_data_profiled<T: serde::Serialize>(input: &T) -> Result<(ParsedData, Profile), Error> {
+	let mut serializer = Parser::new(false, true);
+	let data = input.serialize(&mut serializer)?;
+	Ok((data, serializer.profile.get()))
+}
+
+/// same as [`to_data`], but returns [`Error::DepthLimitExceeded`] if the output is nested deeper than `max_depth`
+#[cfg(feature = "std")]
+fn to_data_limited<T: serde::Serialize>(input: &T, max_depth: usize) -> Result<ParsedData, Error> {
+	let data = to_data(input)?;
+	data.check_depth(max_depth)?;
+	Ok(data)
+}
+
+/// parse a [`ParsedData`] data into your type. values are moved out of `input` instead of being cloned, so `input` is left emptied afterwards.
+/// returns [`Error::DepthLimitExceeded`] without touching `input` if it is nested deeper than [`MAX_DEPTH`].
+/// the lifetime `'a` is never borrowed from, prefer [`from_data_owned`] in generic code
 pub fn from_data<'a, T>(input: &mut ParsedData) -> Result<T, Error>
 where
 	T: serde::Deserialize<'a>
 {
+	from_data_with_max_depth(input, MAX_DEPTH)
+}
+
+/// same as [`from_data`], but bound by [`DeserializeOwned`] instead of a free lifetime. nothing is ever borrowed from `input`, so this is easier to call from generic code
+pub fn from_data_owned<T: DeserializeOwned>(input: &mut ParsedData) -> Result<T, Error> {
+	from_data(input)
+}
+
+/// same as [`from_data_owned`], but `transform` can rewrite every leaf before your type reads it, which is useful for migrations like a change of units.
+/// `transform` gets the id of the leaf (see [`field_id`]) and its value, in the same order as [`ParsedData::leaves`]
+pub fn from_data_with<T: DeserializeOwned>(input: &mut ParsedData, mut transform: impl FnMut(&str, &mut DataEnum)) -> Result<T, Error> {
+	input.check_depth(MAX_DEPTH)?;
+	input.walk_mut(&mut |id, data| if !matches!(data, DataEnum::Node(_) | DataEnum::Map(_) | DataEnum::Enum(_, _)) {
+		transform(id, data);
+	});
+	from_data(input)
+}
+
+/// same as [`from_data_owned`], but only deserializes the value at `path`, see [`ParsedData::get_path`] for the format of `path`. only that value is moved out of `input`, the rest is left untouched.
+/// returns [`Error::UnknownField`] if the path does not exist
+pub fn from_data_at<T: DeserializeOwned>(input: &mut ParsedData, path: &str) -> Result<T, Error> {
+	from_data(input.path_mut(path)?)
+}
+
+/// same as [`from_data`], but with your own depth limit instead of [`MAX_DEPTH`]. large limits can overflow the stack
+pub fn from_data_with_max_depth<'a, T>(input: &mut ParsedData, max_depth: usize) -> Result<T, Error>
+where
+	T: serde::Deserialize<'a>
+{
+	input.check_depth(max_depth)?;
+	let mut deserializer = DeParser {
+		data: input,
+		strict: false,
+		paths: false,
+	};
+	T::deserialize(&mut deserializer)
+}
+
+/// same as [`from_data`], but leaves `input` untouched so it can be deserialized again. `input` is cloned once, which costs as much as a [`to_data`] of your type
+pub fn from_data_ref<T: DeserializeOwned>(input: &ParsedData) -> Result<T, Error> {
+	from_data(&mut input.clone())
+}
+
+/// same as [`from_data`], but returns [`Error::UnknownField`] if `input` contains fields that your type does not have, which is useful to catch typos in field names
+pub fn from_data_strict<'a, T>(input: &mut ParsedData) -> Result<T, Error>
+where
+	T: serde::Deserialize<'a>
+{
+	input.check_depth(MAX_DEPTH)?;
 	let mut deserializer = DeParser {
-		data: input
+		data: input,
+		strict: true,
+		paths: false,
 	};
 	T::deserialize(&mut deserializer)
 }
 
+/// same as [`from_data_ref`], but does not stop at the first field that fails. every failed field is reported with its id (see [`field_id`]) and replaced by its value in `T::default()`, then deserializing is tried again,
+/// so all bad fields of a large tree can be found at once. failed elements of sequences that the default value does not have are dropped instead.
+/// returns the output only if no field failed
+pub fn from_data_collect<T>(input: &ParsedData) -> Result<T, Vec<(String, Error)>>
+where
+	T: DeserializeOwned + Serialize + Default
+{
+	let root = join_id("", &input.name);
+	input.check_depth(MAX_DEPTH).map_err(|error| vec!((root.clone(), error)))?;
+	let default = to_data(&T::default()).map_err(|error| vec!((root.clone(), error)))?;
+	let mut input = input.clone();
+	let mut errors: Vec<(String, Error)> = vec!();
+	loop {
+		let mut deserializer = DeParser {
+			data: &mut input.clone(),
+			strict: false,
+			paths: true,
+		};
+		let (path, error) = match T::deserialize(&mut deserializer) {
+			Ok(output) if errors.is_empty() => return Ok(output),
+			Ok(_) => return Err(errors),
+			Err(Error::At(path, error)) => (path, *error),
+			Err(error) => (String::new(), error),
+		};
+		let id = root.clone() + &path;
+		// a field that still fails after being replaced can not be recovered
+		let repeated = errors.iter().any(|(before, _)| *before == id);
+		errors.push((id, error));
+		if repeated || !input.replace_with_default(&path, &default) {
+			return Err(errors);
+		}
+	}
+}
+
+/// overlay `patch` onto `base`.
+///
+/// children of nodes and enums are matched by their name (or by their index if they have no name) and merged recursively, children only in `base` are left untouched and children only in `patch` are appended.
+/// map entries merge their values. everything else, including values whose variants are different or enums with different variants, are overwritten by `patch`
+pub fn merge(base: &mut ParsedData, patch: &ParsedData) {
+	match (&mut base.data, &patch.data) {
+		(DataEnum::Node(base_inner), DataEnum::Node(patch_inner)) => merge_children(base_inner, patch_inner),
+		(DataEnum::Enum(base_variant, base_inner), DataEnum::Enum(patch_variant, patch_inner)) if base_variant == patch_variant => merge_children(base_inner, patch_inner),
+		(DataEnum::Map(base_box), DataEnum::Map(patch_box)) => merge(&mut base_box.1, &patch_box.1),
+		_ => base.data = patch.data.clone(),
+	}
+}
+
+fn merge_children(base: &mut Vec<ParsedData>, patch: &[ParsedData]) {
+	for (index, patch_data) in patch.iter().enumerate() {
+		let found = if patch_data.name.is_empty() {
+			base.get_mut(index)
+		}else {
+			base.iter_mut().find(|data| data.name == patch_data.name)
+		};
+		match found {
+			Some(base_data) => merge(base_data, patch_data),
+			None => base.push(patch_data.clone()),
+		}
+	}
+}
+
+/// render a [`ParsedData`] as a indented tree, one value per line, which is easier to read than `{:?}`
+pub fn to_pretty_string(data: &ParsedData) -> String {
+	let mut output = String::new();
+	write_pretty(&data.name, data, 0, &mut output);
+	output
+}
+
+fn write_pretty(label: &str, data: &ParsedData, depth: usize, output: &mut String) {
+	output.push_str(&"  ".repeat(depth));
+	if !label.is_empty() {
+		output.push_str(&format!("{}: ", label));
+	}
+	let line = match &data.data {
+		DataEnum::Node(_) => "node".to_string(),
+		DataEnum::Map(_) => "map".to_string(),
+		DataEnum::Enum(variant, _) => format!("enum {}", variant),
+		DataEnum::Data(inner) => format!("data {:?}", inner),
+		DataEnum::String(inner) => format!("string {:?}", inner),
+		DataEnum::Int(inner, range) => format!("int {} ({:?})", inner, range),
+		DataEnum::Float(inner) => format!("float {:?}", inner),
+		DataEnum::Bool(inner) => format!("bool {}", inner),
+		DataEnum::Some(_) => "some".to_string(),
+		DataEnum::None => "none".to_string(),
+	};
+	output.push_str(&line);
+	output.push('\n');
+	match &data.data {
+		DataEnum::Node(inner) | DataEnum::Enum(_, inner) => {
+			for inside in inner {
+				write_pretty(&inside.name, inside, depth + 1, output);
+			}
+		},
+		DataEnum::Map(box_inside) => {
+			write_pretty("key", &box_inside.0, depth + 1, output);
+			write_pretty("value", &box_inside.1, depth + 1, output);
+		},
+		DataEnum::Some(inner) => write_pretty(&inner.name, inner, depth + 1, output),
+		_ => {}
+	}
+}
+
 macro_rules! impl_into_parsed_data {
 	($t: ty, $s: tt) => {
 		impl From<$t> for ParsedData {
 			fn from(input: $t) -> Self {
 				ParsedData {
 					data: DataEnum::$s(input.into()),
-					name: "".to_string(),
-					need_delete: false
+					name: "".to_string()
 				}
 			}
 		}
@@ -148,39 +988,80 @@ macro_rules! impl_into_parsed_data {
 			fn from(input: $t) -> Self {
 				ParsedData {
 					data: DataEnum::$s(input.into(), $b),
-					name: "".to_string(),
-					need_delete: false
+					name: "".to_string()
 				}
 			}
 		}
 	};
 }
 
+/// int types that fit in a [`DataEnum::Int`], see [`ParsedData::int`]
+pub trait IntType: Into<i128> {
+	/// the range of the type, which [`to_data`] stores next to the value
+	fn range() -> RangeInclusive<i128>;
+}
+
+macro_rules! impl_int_type {
+	($($t: ty),*) => {
+		$(
+			impl IntType for $t {
+				fn range() -> RangeInclusive<i128> {
+					<$t>::MIN.into()..=<$t>::MAX.into()
+				}
+			}
+		)*
+	};
+}
+
+impl_int_type!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
 macro_rules! impl_serdelize {
 	($i: ident, $t: ty) => {
 		fn $i(self, input: $t) -> Result<ParsedData, Error> {
-			Ok(input.into())
+			Ok(self.counted(input.into()))
+		}
+	};
+	($i: ident, $t: ty, $r: ident) => {
+		fn $i(self, input: $t) -> Result<ParsedData, Error> {
+			Ok(self.$r(input.into()))
 		}
 	};
 }
 
 impl_into_parsed_data!(bool, Bool);
-impl_into_parsed_data!(i8, Int, i8::MIN.into()..=i8::MAX.into());
-impl_into_parsed_data!(i16, Int, i16::MIN.into()..=i16::MAX.into());
-impl_into_parsed_data!(i32, Int, i32::MIN.into()..=i32::MAX.into());
-impl_into_parsed_data!(i64, Int, i64::MIN.into()..=i64::MAX.into());
-impl_into_parsed_data!(u8, Int, u8::MIN.into()..=u8::MAX.into());
-impl_into_parsed_data!(u16, Int, u16::MIN.into()..=u16::MAX.into());
-impl_into_parsed_data!(u32, Int, u32::MIN.into()..=u32::MAX.into());
-impl_into_parsed_data!(u64, Int, u64::MIN.into()..=u64::MAX.into());
+impl_into_parsed_data!(i8, Int, i8::range());
+impl_into_parsed_data!(i16, Int, i16::range());
+impl_into_parsed_data!(i32, Int, i32::range());
+impl_into_parsed_data!(i64, Int, i64::range());
+impl_into_parsed_data!(u8, Int, u8::range());
+impl_into_parsed_data!(u16, Int, u16::range());
+impl_into_parsed_data!(u32, Int, u32::range());
+impl_into_parsed_data!(u64, Int, u64::range());
+impl_into_parsed_data!(i128, Int, i128::range());
 impl_into_parsed_data!(f32, Float);
 impl_into_parsed_data!(f64, Float);
+#[cfg(feature = "half")]
+impl_into_parsed_data!(half::f16, Float);
 impl_into_parsed_data!(char, String);
 impl_into_parsed_data!(&str, String);
 impl_into_parsed_data!(String, String);
 impl_into_parsed_data!(&[u8], Data);
+impl_into_parsed_data!(Vec<u8>, Data);
 impl_into_parsed_data!(Vec<ParsedData>, Node);
 
+/// since [`DataEnum::Int`] stores an `i128`, values above `i128::MAX` return a error instead of being clamped, the same as [`to_data`] does for them.
+impl TryFrom<u128> for ParsedData {
+	type Error = Error;
+
+	fn try_from(input: u128) -> Result<Self, Error> {
+		let value = i128::try_from(input).map_err(|_| Error::SerdeError(format!("u128 value {} is larger than i128::MAX", input)))?;
+		Ok(ParsedData {
+			data: DataEnum::Int(value, 0..=i128::MAX),
+			name: "".to_string()
+		})
+	}
+}
+
 impl<'a> ser::Serializer for &'a mut Parser {
 	type Ok = ParsedData;
 	type Error = Error;
@@ -193,30 +1074,46 @@ impl<'a> ser::Serializer for &'a mut Parser {
 	type SerializeStructVariant = Layer;
 
 	impl_serdelize!(serialize_bool, bool);
-	impl_serdelize!(serialize_i8, i8);
-	impl_serdelize!(serialize_i16, i16);
-	impl_serdelize!(serialize_i32, i32);
-	impl_serdelize!(serialize_i64, i64);
-	impl_serdelize!(serialize_u8, u8);
-	impl_serdelize!(serialize_u16, u16);
-	impl_serdelize!(serialize_u32, u32);
-	impl_serdelize!(serialize_u64, u64);
+	impl_serdelize!(serialize_i8, i8, ranged);
+	impl_serdelize!(serialize_i16, i16, ranged);
+	impl_serdelize!(serialize_i32, i32, ranged);
+	impl_serdelize!(serialize_i64, i64, ranged);
+	impl_serdelize!(serialize_u8, u8, ranged);
+	impl_serdelize!(serialize_u16, u16, ranged);
+	impl_serdelize!(serialize_u32, u32, ranged);
+	impl_serdelize!(serialize_u64, u64, ranged);
+	impl_serdelize!(serialize_i128, i128, ranged);
 	impl_serdelize!(serialize_f32, f32);
 	impl_serdelize!(serialize_f64, f64);
 	impl_serdelize!(serialize_char, char);
 	impl_serdelize!(serialize_str, &str);
 	impl_serdelize!(serialize_bytes, &[u8]);
 
+	/// values above `i128::MAX` can not be stored in [`DataEnum::Int`], so they will return a error, unless they are a map key which is stored as a [`DataEnum::String`] of its digits
+	fn serialize_u128(self, input: u128) -> Result<ParsedData, Error> {
+		if input > i128::MAX as u128 && self.key {
+			return Ok(self.counted(input.to_string().into()));
+		}
+		Ok(self.ranged(input.try_into()?))
+	}
+
 	fn serialize_none(self) -> Result<ParsedData, Error> {
-		Ok(ParsedData {
+		Ok(self.counted(ParsedData {
 			data: DataEnum::None,
-			name: "".to_string(),
-			need_delete: false
-		})
+			name: "".to_string()
+		}))
 	}
 
 	fn serialize_some<T: ?Sized + Serialize>(self, input: &T) -> Result<ParsedData, Error> {
-		input.serialize(self)
+		let inner = input.serialize(&mut *self)?;
+		if let DataEnum::None | DataEnum::Some(_) = inner.data {
+			Ok(self.counted(ParsedData {
+				name: inner.name.clone(),
+				data: DataEnum::Some(Box::new(inner))
+			}))
+		}else {
+			Ok(inner)
+		}
 	}
 
 	fn serialize_unit(self) -> Result<ParsedData, Error> {
@@ -224,45 +1121,42 @@ impl<'a> ser::Serializer for &'a mut Parser {
 	}
 
 	fn serialize_unit_struct(self, name: &'static str) -> Result<ParsedData, Error> {
-		Ok(ParsedData {
+		Ok(self.counted(ParsedData {
 			data: DataEnum::None,
-			name: name.to_string(),
-			need_delete: false
-		})
+			name: name.to_string()
+		}))
 	}
 
 	fn serialize_unit_variant(self, name: &'static str, _: u32, input: &'static str) -> Result<ParsedData, Error> {
-		Ok(ParsedData {
+		Ok(self.counted(ParsedData {
 			data: DataEnum::Enum(input.into(), vec!()),
-			name: name.to_string(),
-			need_delete: false
-		})
+			name: name.to_string()
+		}))
 	}
 
 	fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _: &'static str, inner: u32, variant: &'static str, value: &T) -> Result<ParsedData, Error> {
-		let back = value.serialize(self)?;
-		Ok(ParsedData{
+		let back = value.serialize(&mut *self)?;
+		Ok(self.counted(ParsedData{
 			data: DataEnum::Enum(variant.into(), vec!(back)),
-			name: inner.to_string(),
-			need_delete: false
-		})
+			name: inner.to_string()
+		}))
 	}
 
 	fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<ParsedData, Error> {
 		value.serialize(self)
 	}
 	fn serialize_seq(self, inner: Option<usize>) -> Result<Layer, Error> { 
-		Ok(Layer::new(match inner {
-			Some(t) => t.to_string(),
-			None => String::new()
-		}))
+		Ok(match inner {
+			Some(t) => Layer::new(t.to_string(), t, self.clone()),
+			None => Layer::new("", self.capacity, self.clone())
+		})
 	}
-	fn serialize_tuple(self, size: usize) -> Result<Layer, Error> { Ok(Layer::new(size.to_string())) }
-	fn serialize_struct(self, name: &'static str, _: usize ) -> Result<Layer, Error> { Ok(Layer::new(name)) }
-	fn serialize_tuple_struct(self, name: &'static str, _: usize) -> Result<Layer, Error> { Ok(Layer::new(name)) }
-	fn serialize_tuple_variant(self, _: &'static str, _: u32, variant: &'static str, _: usize) -> Result<Layer, Error> { Ok(Layer::new(variant)) }
-	fn serialize_map(self, _:  Option<usize>) -> Result<Layer, Error> { Ok(Layer::default()) }
-	fn serialize_struct_variant(self, _: &'static str, _: u32, variant: &'static str, _: usize) -> Result<Layer, Error> { Ok(Layer::new(variant)) }
+	fn serialize_tuple(self, size: usize) -> Result<Layer, Error> { Ok(Layer::new(size.to_string(), size, self.clone())) }
+	fn serialize_struct(self, name: &'static str, len: usize ) -> Result<Layer, Error> { Ok(Layer::new(name, len, self.clone())) }
+	fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Layer, Error> { Ok(Layer::new(name, len, self.clone())) }
+	fn serialize_tuple_variant(self, _: &'static str, _: u32, variant: &'static str, len: usize) -> Result<Layer, Error> { Ok(Layer::new(variant, len, self.clone())) }
+	fn serialize_map(self, inner:  Option<usize>) -> Result<Layer, Error> { Ok(Layer::new("", inner.unwrap_or(self.capacity), self.clone())) }
+	fn serialize_struct_variant(self, _: &'static str, _: u32, variant: &'static str, len: usize) -> Result<Layer, Error> { Ok(Layer::new(variant, len, self.clone())) }
 }
 
 impl ser::SerializeSeq for Layer {
@@ -273,18 +1167,19 @@ impl ser::SerializeSeq for Layer {
 	where
 		T: ?Sized + Serialize,
 	{
-		let len = self.inner.len();
+		let name = self.inner.len().to_string();
+		let data = self.element(&name, value)?;
 		self.inner.push(ParsedData {
-			name: len.to_string(),
-			..value.serialize(&mut Parser {})?
+			name,
+			..data
 		});
 		Ok(())
 	}
 	fn end(self) -> Result<ParsedData, Error> { 
-		Ok(ParsedData {
+		Ok(self.parser.counted(ParsedData {
 			name: self.final_name.clone(), 
 			..self.inner.into()
-		}) 
+		})) 
 	}
 }
 
@@ -296,18 +1191,19 @@ impl ser::SerializeTuple for Layer {
 	where
 		T: ?Sized + Serialize,
 	{
-		let len = self.inner.len();
+		let name = self.inner.len().to_string();
+		let data = self.element(&name, value)?;
 		self.inner.push(ParsedData {
-			name: len.to_string(),
-			..value.serialize(&mut Parser {})?
+			name,
+			..data
 		});
 		Ok(())
 	}
 	fn end(self) -> Result<ParsedData, Error> { 
-		Ok(ParsedData {
+		Ok(self.parser.counted(ParsedData {
 			name: self.final_name.clone(), 
 			..self.inner.into()
-		}) 
+		})) 
 	}
 }
 
@@ -319,14 +1215,15 @@ impl ser::SerializeTupleStruct for Layer {
 	where
 		T: ?Sized + Serialize,
 	{
-		self.inner.push(value.serialize(&mut Parser {})?);
+		let data = self.element("", value)?;
+		self.inner.push(data);
 		Ok(())
 	}
 	fn end(self) -> Result<ParsedData, Error> { 
-		Ok(ParsedData {
+		Ok(self.parser.counted(ParsedData {
 			name: self.final_name.clone(), 
 			..self.inner.into()
-		}) 
+		})) 
 	}
 }
 
@@ -338,21 +1235,32 @@ impl ser::SerializeTupleVariant for Layer {
 	where
 		T: ?Sized + Serialize,
 	{
-		let len = self.inner.len();
+		let name = self.inner.len().to_string();
+		let data = self.element(&name, value)?;
 		self.inner.push(ParsedData {
-			name: len.to_string(),
-			..value.serialize(&mut Parser {})?
+			name,
+			..data
 		});
 		
 		Ok(())
 	}
 
 	fn end(self) -> Result<ParsedData, Error> { 
-		Ok(ParsedData {
+		Ok(self.parser.counted(ParsedData {
 			name: self.final_name.clone(), 
-			data: DataEnum::Enum(self.final_name.clone(), self.inner),
-			need_delete: false
-		}) 
+			data: DataEnum::Enum(self.final_name.clone(), self.inner)
+		})) 
+	}
+}
+
+/// name of a map entry, only keys that are string int float or bool give a name
+fn key_name(key: &DataEnum) -> String {
+	match key {
+		DataEnum::String(inner) => inner.to_string(),
+		DataEnum::Int(inner, _) => inner.to_string(),
+		DataEnum::Float(inner) => inner.to_string(),
+		DataEnum::Bool(inner) => inner.to_string(),
+		_ => "".to_string()
 	}
 }
 
@@ -364,40 +1272,43 @@ impl ser::SerializeMap for Layer {
 	where
 		T: ?Sized + Serialize,
 	{
-		let key = key.serialize(&mut Parser {})?;
-		let name = match key.data {
-			DataEnum::String(ref inner) => inner.to_string(),
-			DataEnum::Int(inner, _) => inner.to_string(),
-			DataEnum::Float(inner) => inner.to_string(),
-			DataEnum::Bool(inner) => inner.to_string(),
-			_ => "".to_string()
-		};
-		let data = ParsedData {
-			data: key.data, // Temporary Value
-			name,
-			need_delete: false
-		};
+		let key = self.key(key)?;
+		let name = key_name(&key.data);
+		if self.parser.strict && !name.is_empty() && !self.keys.insert(name.clone()) {
+			return Err(Error::DuplicateKey(name));
+		}
+		// the whole key is kept as a temporary value, so keys like tuples or structs can be deserialized back
+		let data = self.parser.counted(ParsedData {
+			data: DataEnum::Map(Box::new((key, ParsedData::default()))),
+			name
+		});
 		self.inner.push(data);
+		self.key_pending = true;
 		Ok(())
 	}
 
+	/// returns [`Error::Syntax`] if there is no key waiting for this value, which only happens with hand written [`Serialize`] impls
 	fn serialize_value<T: ?Sized + Serialize>(&mut self, input: &T) -> Result<(), Error> {
-		let parse = input.serialize(&mut Parser {})?;
-		let len = self.inner.len() - 1;
-		let key_data = self.inner[len].clone();
-		self.inner[len] = ParsedData {
-			name: key_data.name.clone(),
-			data: DataEnum::Map(Box::new((key_data, parse))),
-			need_delete: false,
+		if !self.key_pending {
+			return Err(Error::Syntax);
+		}
+		self.key_pending = false;
+		let name = match self.inner.last() {
+			Some(data) => data.name.clone(),
+			None => String::new(),
 		};
+		let parse = input.serialize(&mut Parser { root: false, key: false, ..self.parser.clone() }).map_err(|error| self.error_at(&name, error.at("")))?;
+		if let Some(ParsedData { data: DataEnum::Map(box_inside), .. }) = self.inner.last_mut() {
+			box_inside.1 = parse;
+		}
 		Ok(())
 	}
 
 	fn end(self) -> Result<ParsedData, Error> { 
-		Ok(ParsedData {
+		Ok(self.parser.counted(ParsedData {
 			name: self.final_name.clone(), 
 			..self.inner.into()
-		}) 
+		})) 
 	}
 }
 
@@ -409,18 +1320,20 @@ impl ser::SerializeStruct for Layer {
 	where
 		T: ?Sized + Serialize,
 	{
-		self.inner.push(value.serialize(&mut Parser {})?);
-		let len = self.inner.len() - 1;
-		self.inner[len].name = name.into();
+		let data = self.element(name, value)?;
+		self.inner.push(ParsedData {
+			name: name.into(),
+			..data
+		});
 
 		Ok(())
 	}
 
 	fn end(self) -> Result<ParsedData, Error> { 
-		Ok(ParsedData {
+		Ok(self.parser.counted(ParsedData {
 			name: self.final_name.clone(), 
 			..self.inner.into()
-		}) 
+		})) 
 	}
 }
 
@@ -432,20 +1345,21 @@ impl ser::SerializeStructVariant for Layer {
 	where
 		T: ?Sized + Serialize,
 	{
-		self.inner.push(value.serialize(&mut Parser {})?);
-		let len = self.inner.len() - 1;
-		self.inner[len].name = name.into();
+		let data = self.element(name, value)?;
+		self.inner.push(ParsedData {
+			name: name.into(),
+			..data
+		});
 		// println!("{:?}", name);
 
 		Ok(())
 	}
 
 	fn end(self) -> Result<ParsedData, Error> { 
-		Ok(ParsedData{
+		Ok(self.parser.counted(ParsedData{
 			data: DataEnum::Enum(self.final_name.clone(), self.inner),
-			name: self.final_name,
-			need_delete: false
-		})
+			name: self.final_name
+		}))
 	}
 }
 
@@ -453,22 +1367,18 @@ macro_rules! deserialize {
 	($i1: ident, $i2: ident,$s: tt , $t:ty) => {
 		fn $i1<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
 			if let DataEnum::$s(t) = &self.data.data {
-				let value = input.$i2(t.clone() as $t)?;
-				self.data.need_delete = true;
-				Ok(value)
+				input.$i2(*t as $t)
 			}else {
-				Err(Error::UnexpectedType(stringify!($t).to_string()))
+				Err(Error::UnexpectedType(stringify!($t).to_string(), self.data.data.type_name()))
 			}
 		}
 	};
 	($i1: ident, $i2: ident,$s: tt , $t:ty, $b: ident) => {
 		fn $i1<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
 			if let DataEnum::$s(t, _) = &self.data.data {
-				let value = input.$i2(t.clone() as $t)?;
-				self.data.need_delete = true;
-				Ok(value)
+				input.$i2(<$t>::try_from(*t).map_err(|_| Error::Overflow(*t, stringify!($t)))?)
 			}else {
-				Err(Error::UnexpectedType(stringify!($t).to_string()))
+				Err(Error::UnexpectedType(stringify!($t).to_string(), self.data.data.type_name()))
 			}
 		}
 	}
@@ -485,28 +1395,36 @@ impl<'a, 'de> Deserializer<'de> for &'a mut DeParser<'_> {
 	deserialize!(deserialize_u16, visit_u16, Int, u16, true);
 	deserialize!(deserialize_u32, visit_u32, Int, u32, true);
 	deserialize!(deserialize_u64, visit_u64, Int, u64, true);
+	deserialize!(deserialize_i128, visit_i128, Int, i128, true);
+	deserialize!(deserialize_u128, visit_u128, Int, u128, true);
 	deserialize!(deserialize_f32, visit_f32, Float, f32);
 	deserialize!(deserialize_f64, visit_f64, Float, f64);
-	deserialize!(deserialize_string, visit_string, String, String);
 	fn deserialize_any<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
-		match &self.data.data {
+		match &mut self.data.data {
 			DataEnum::Node(inner) => {
-				let mut fields = vec!();
-				if inner.len() == 1 {
-					*self.data = inner[0].clone();
+				if inner.len() == 1 && inner[0].name.is_empty() {
+					*self.data = inner.pop().unwrap();
 					return self.deserialize_any(input)
 				}
-				for data in inner {
-					if data.name.is_empty() {
-						return self.deserialize_seq(input)
+				if !inner.is_empty() && inner.iter().all(|data| matches!(data.data, DataEnum::Map(_))) {
+					return self.deserialize_map(input)
+				}
+				// elements of sequences and tuples are named by their index, so children without names or named by their index are visited as a seq.
+				// others as a map whose keys are the names, or the index for children without a name
+				if inner.iter().enumerate().all(|(index, data)| !matches!(data.data, DataEnum::Map(_)) && (data.name.is_empty() || data.name == index.to_string())) {
+					return self.deserialize_seq(input)
+				}
+				let fields = core::mem::take(inner).into_iter().enumerate().map(|(index, data)| {
+					let name = if data.name.is_empty() {
+						index.to_string()
 					}else {
-						fields.push(ParsedData {
-							name: data.name.clone(),
-							data: DataEnum::Map(Box::new((data.name.clone().into(), data.clone()))),
-							need_delete: false,
-						});
+						data.name.clone()
+					};
+					ParsedData {
+						data: DataEnum::Map(Box::new((name.as_str().into(), data))),
+						name,
 					}
-				}
+				}).collect();
 				*self.data = ParsedData {
 					data: DataEnum::Node(fields),
 					..Default::default()
@@ -517,58 +1435,66 @@ impl<'a, 'de> Deserializer<'de> for &'a mut DeParser<'_> {
 			DataEnum::Enum(_, _) => self.deserialize_enum("", &[], input),
 			DataEnum::Data(_) => self.deserialize_bytes(input),
 			DataEnum::String(_) => self.deserialize_string(input),
-			DataEnum::Int(_, _) => self.deserialize_i64(input),
+			DataEnum::Int(inner, _) => {
+				if i64::try_from(*inner).is_ok() {
+					self.deserialize_i64(input)
+				}else if u64::try_from(*inner).is_ok() {
+					self.deserialize_u64(input)
+				}else {
+					self.deserialize_i128(input)
+				}
+			},
 			DataEnum::Float(_) => self.deserialize_f64(input),
 			DataEnum::Bool(_) => self.deserialize_bool(input),
+			DataEnum::Some(_) => self.deserialize_option(input),
 			DataEnum::None => self.deserialize_unit(input),
 		}
 	}
 
 	fn deserialize_char<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
 		if let DataEnum::String(t) = &self.data.data {
-			let value = input.visit_char(t.chars().next().unwrap())?;
-			self.data.need_delete = true;
-			Ok(value)
+			let mut chars = t.chars();
+			match (chars.next(), chars.next()) {
+				(Some(c), None) => input.visit_char(c),
+				_ => Err(Error::UnexpectedType(stringify!(char).to_string(), self.data.data.type_name())),
+			}
 		}else {
-			Err(Error::UnexpectedType(stringify!($t).to_string()))
+			Err(Error::UnexpectedType(stringify!(char).to_string(), self.data.data.type_name()))
 		}
 	}
 
 	fn deserialize_str<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
 		if let DataEnum::String(t) = &self.data.data {
-			let value = input.visit_str(t)?;
-			self.data.need_delete = true;
-			Ok(value)
+			input.visit_str(t)
 		}else {
-			Err(Error::UnexpectedType(stringify!(str).to_string()))
+			Err(Error::UnexpectedType(stringify!(str).to_string(), self.data.data.type_name()))
 		}
 	}
 
-	fn deserialize_bytes<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
-		if let DataEnum::Data(t) = &self.data.data {
-			let value = input.visit_bytes(t)?;
-			self.data.need_delete = true;
-			Ok(value)
+	fn deserialize_string<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
+		if let DataEnum::String(t) = &mut self.data.data {
+			input.visit_string(core::mem::take(t))
 		}else {
-			Err(Error::UnexpectedType(stringify!(&[u8]).to_string()))
+			Err(Error::UnexpectedType(stringify!(String).to_string(), self.data.data.type_name()))
 		}
 	}
 
+	// the buffer is moved out with `visit_byte_buf` even when borrowed bytes are asked for, visitors that only take `visit_bytes` still get a borrow through serde's default forwarding
+	fn deserialize_bytes<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> { self.deserialize_byte_buf(input) }
+
 	fn deserialize_byte_buf<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
-		if let DataEnum::Data(t) = &self.data.data {
-			let value = input.visit_byte_buf(t.to_vec())?;
-			self.data.need_delete = true;
-			Ok(value)
+		if let DataEnum::Data(t) = &mut self.data.data {
+			input.visit_byte_buf(core::mem::take(t))
 		}else {
-			Err(Error::UnexpectedType(stringify!(&[u8]).to_string()))
+			Err(Error::UnexpectedType(stringify!(&[u8]).to_string(), self.data.data.type_name()))
 		}
 	}
 
 	fn deserialize_option<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
-		if let DataEnum::None = self.data.data {
-			input.visit_none()
-		}else {
-			input.visit_some(self)
+		match &mut self.data.data {
+			DataEnum::None => input.visit_none(),
+			DataEnum::Some(inner) => input.visit_some(&mut DeParser { data: inner, strict: self.strict, paths: self.paths }),
+			_ => input.visit_some(self),
 		}
 	}
 
@@ -576,119 +1502,168 @@ impl<'a, 'de> Deserializer<'de> for &'a mut DeParser<'_> {
 		if let DataEnum::None = self.data.data {
 			input.visit_unit()
 		}else {
-			Err(Error::UnexpectedType(stringify!(None).to_string()))
+			Err(Error::UnexpectedType(stringify!(None).to_string(), self.data.data.type_name()))
 		}
 	}
 
 	fn deserialize_unit_struct<V: Visitor<'de>>(self,_:&'static str, input: V) -> Result<V::Value, Error> { self.deserialize_unit(input) }
 
-	fn deserialize_newtype_struct<V: Visitor<'de>>(self,_:&'static str, input: V) -> Result<V::Value, Error> { self.deserialize_unit(input) }
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self,_:&'static str, input: V) -> Result<V::Value, Error> { input.visit_newtype_struct(self) }
 
 	fn deserialize_seq<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
 		if let DataEnum::Node(vec) = &mut self.data.data {
-			vec.retain(|data| !data.need_delete);
+			input.visit_seq(DeLayer::from(core::mem::take(vec), self.strict, self.paths))
 		}else {
-			return Err(Error::UnexpectedType(stringify!(seq).to_string()));
+			Err(Error::UnexpectedType(stringify!(seq).to_string(), self.data.data.type_name()))
 		}
-		input.visit_seq(DeLayer::from(self.data))
 	}
 
 	fn deserialize_map<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
-		if let DataEnum::Node(_) = self.data.data {
+		if let DataEnum::Node(vec) = &mut self.data.data {
+			input.visit_map(DeMap::from(core::mem::take(vec), self.strict, self.paths))
 		}else {
-			return Err(Error::UnexpectedType(stringify!(seq).to_string()));
+			Err(Error::UnexpectedType(stringify!(map).to_string(), self.data.data.type_name()))
 		}
-		input.visit_map(DeMap::from(self.data))
 	}
 
-	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _: &'static str, _: usize, input: V) -> Result<V::Value, Error> { self.deserialize_seq(input) }
+	fn deserialize_tuple_struct<V: Visitor<'de>>(self, _: &'static str, len: usize, input: V) -> Result<V::Value, Error> { self.deserialize_tuple(len, input) }
 
-	fn deserialize_tuple<V: Visitor<'de>>(self,_: usize, input: V) -> Result<V::Value, Error> { self.deserialize_seq(input) }
+	/// elements are visited in order like sequences, but extra elements are a error instead of being dropped, as a tuple can not have more than `len`
+	fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, input: V) -> Result<V::Value, Error> {
+		if let DataEnum::Node(vec) = &self.data.data {
+			if vec.len() > len {
+				return Err(<Error as serde::de::Error>::invalid_length(vec.len(), &input));
+			}
+		}
+		self.deserialize_seq(input)
+	}
 
 	fn deserialize_struct<V: Visitor<'de>>(self,_: &'static str, fields: &'static [&'static str], input: V) -> Result<V::Value, Error> {
 		if let DataEnum::Node(vec) = &mut self.data.data {
 			let mut output = vec!();
-			for index in 0..vec.len() {
+			for (index, data) in core::mem::take(vec).into_iter().enumerate() {
+				// children keep their names so fields are matched by name, as skipped or reordered fields shift the position of the ones after them.
+				// only children without a name or named by their index, like elements of a sequence, fall back to the field at their position
+				let positional = data.name.is_empty() || data.name == index.to_string();
+				let key = if positional && !fields.contains(&data.name.as_str()) {
+					fields.get(index).copied().unwrap_or(&data.name)
+				}else {
+					data.name.as_str()
+				};
 				output.push(ParsedData {
-					data: DataEnum::Map(Box::new((fields[index].into(), vec[index].clone()))),
-					name: String::new(),
-					need_delete: false,
+					data: DataEnum::Map(Box::new((key.into(), data))),
+					name: String::new()
 				});
 			}
 			*self.data = ParsedData {
 				data: DataEnum::Node(output),
-				name: String::new(),
-				need_delete: self.data.need_delete
+				name: String::new()
 			}
 		}else {
-			return Err(Error::UnexpectedType(stringify!(struct).to_string()));
+			return Err(Error::UnexpectedType(stringify!(struct).to_string(), self.data.data.type_name()));
 		}
 		self.deserialize_map(input)
 	}
 
 	fn deserialize_enum<V: Visitor<'de>>(self, _: &'static str, _: &'static [&'static str], input: V) -> Result<V::Value, Error> {
-		if let DataEnum::Enum(value, inner) = &self.data.data {
-			if inner.is_empty() {
-				return input.visit_enum(value.clone().into_deserializer());
-			}else {
+		match &mut self.data.data {
+			DataEnum::Enum(value, inner) => {
+				if inner.is_empty() {
+					input.visit_enum(core::mem::take(value).into_deserializer())
+				}else {
+					input.visit_enum(DeEnum { 
+						inner: &mut DeParser { data: self.data, strict: self.strict, paths: self.paths },
+					})
+				}
+			},
+			// unit variants written as plain strings, such as the ones from `from_json`
+			DataEnum::String(value) => input.visit_enum(core::mem::take(value).into_deserializer()),
+			// other variants written as a node with the variant as its only child
+			DataEnum::Node(inner) if inner.len() == 1 && !inner[0].name.is_empty() => {
+				let variant = inner.pop().unwrap();
+				let inner = match variant.data {
+					DataEnum::Node(inner) => inner,
+					data => vec!(ParsedData { data, ..Default::default() }),
+				};
+				self.data.data = DataEnum::Enum(variant.name, inner);
 				input.visit_enum(DeEnum { 
-					inner: &mut DeParser { data: self.data },
+					inner: &mut DeParser { data: self.data, strict: self.strict, paths: self.paths },
 				})
-			}
-		}
-		else { 
-			Err(Error::UnexpectedType(stringify!(enum).to_string()))
+			},
+			_ => Err(Error::UnexpectedType(stringify!(enum).to_string(), self.data.data.type_name())),
 		}
 		
 	}
 
 	fn deserialize_identifier<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> { self.deserialize_any(input) }
 
-	fn deserialize_ignored_any<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> { self.deserialize_any(input) }
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
+		if self.strict {
+			return Err(Error::UnknownField(self.data.name.clone()));
+		}
+		self.deserialize_any(input)
+	}
+}
+
+/// mark `error` as coming from the child `name` if `paths` is set
+fn error_at(paths: bool, name: &str, error: Error) -> Error {
+	if paths {
+		error.at(name)
+	}else {
+		error
+	}
 }
 
-impl<'de> SeqAccess<'de> for DeLayer<'_> {
+impl<'de> SeqAccess<'de> for DeLayer {
 	type Error = Error;
 	fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error> 
 	where
 		T: DeserializeSeed<'de>,
 	{   
-		if let DataEnum::Node(vec) = &mut self.inner.data.data {
-			vec.retain(|data| !data.need_delete);
-			if vec.is_empty() {
-				Ok(None)
-			}else {
-				let len = vec.len() - 1;
-				Ok(Some(seed.deserialize(&mut DeParser { data: &mut vec[len] })?))
-			}
-		}else {
-			unreachable!()
+		match self.elements.next() {
+			Some(mut data) => {
+				let name = if self.paths { data.name.clone() } else { String::new() };
+				let value = seed.deserialize(&mut DeParser { data: &mut data, strict: self.strict, paths: self.paths });
+				Ok(Some(value.map_err(|error| error_at(self.paths, &name, error))?))
+			},
+			None => Ok(None),
 		}
 	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.elements.len())
+	}
 }
 
-impl<'de> MapAccess<'de> for DeMap<'_> {
+impl<'de> MapAccess<'de> for DeMap {
 	type Error = Error;
 	fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
 	where 
 		K: DeserializeSeed<'de>,
 	{
-		if let DataEnum::Node(vec) = &mut self.inner.data.data {
-			vec.retain(|data| !data.need_delete);
-			if vec.is_empty() {
-				Ok(None)
-			}else {
-				let len = vec.len() - 1;
-				if let DataEnum::Map(box_inside) = &vec[len].data {
-					let (mut key, value) = *box_inside.clone();
-					self.temp = Some(value);
-					Ok(Some(seed.deserialize(&mut DeParser { data: &mut key })?))
-				}else {
-					Err(Error::UnexpectedType(stringify!(Map).to_string()))
+		let data = match self.entries.next() {
+			Some(data) => data,
+			None => return Ok(None),
+		};
+		match data.data {
+			DataEnum::Map(box_inside) => {
+				let (mut key, value) = *box_inside;
+				let name = if self.paths { key_name(&key.data) } else { String::new() };
+				self.temp = Some((name, value));
+				// string keys can hold numbers that do not fit in a int, such as `u128` keys above `i128::MAX`
+				if let DataEnum::String(key) = key.data {
+					return Ok(Some(seed.deserialize(NameKey(key))?));
 				}
-			}
-		}else {
-			unreachable!()
+				Ok(Some(seed.deserialize(&mut DeParser { data: &mut key, strict: self.strict, paths: self.paths })?))
+			},
+			// named values such as fields from `from_json` use their name as key
+			_ if !data.name.is_empty() => {
+				let key = NameKey(data.name.clone());
+				let name = if self.paths { data.name.clone() } else { String::new() };
+				self.temp = Some((name, data));
+				Ok(Some(seed.deserialize(key)?))
+			},
+			_ => Err(Error::UnexpectedType(stringify!(Map).to_string(), data.data.type_name())),
 		}
 	}
 
@@ -696,12 +1671,12 @@ impl<'de> MapAccess<'de> for DeMap<'_> {
 	where 
 		V: DeserializeSeed<'de>,
 	{
-		if let DataEnum::Node(vec) = &mut self.inner.data.data {
-			let len = vec.len() - 1;
-			vec[len].need_delete = true;
-		}
-		let mut temp = self.temp.clone().unwrap();
-		seed.deserialize(&mut DeParser { data: &mut temp })
+		let (name, mut temp) = self.temp.take().unwrap();
+		seed.deserialize(&mut DeParser { data: &mut temp, strict: self.strict, paths: self.paths }).map_err(|error| error_at(self.paths, &name, error))
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(self.entries.len())
 	}
 }
 
@@ -713,8 +1688,8 @@ impl<'de, 'a> EnumAccess<'de> for DeEnum<'a> {
 	where
 		V: DeserializeSeed<'de>
 	{
-		if let DataEnum::Enum(key, _) = &self.inner.data.data {
-			let val = seed.deserialize(&mut DeParser { data: &mut key.clone().into() })?;
+		if let DataEnum::Enum(key, _) = &mut self.inner.data.data {
+			let val = seed.deserialize(&mut DeParser { data: &mut core::mem::take(key).into(), strict: self.inner.strict, paths: self.inner.paths })?;
 			Ok((val, self))
 		}else {
 			unreachable!()
@@ -726,16 +1701,30 @@ impl<'de, 'a> VariantAccess<'de> for DeEnum<'a> {
 	type Error = Error;
 
 	fn unit_variant(self) -> Result<(), Error> {
-		Err(Error::Syntax)
+		if let DataEnum::Enum(_, inner) = &self.inner.data.data {
+			if inner.is_empty() {
+				Ok(())
+			}else {
+				Err(Error::UnexpectedType(stringify!(unit_variant).to_string(), self.inner.data.data.type_name()))
+			}
+		}else {
+			unreachable!()
+		}
 	}
 
 	fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
 	where
 		T: DeserializeSeed<'de>,
 	{
-		if let DataEnum::Enum(_, inner) = &self.inner.data.data {
+		if let DataEnum::Enum(_, inner) = &mut self.inner.data.data {
+			let data = match inner.first_mut() {
+				Some(data) => data,
+				None => return Err(<Error as serde::de::Error>::invalid_length(0, &"a newtype variant")),
+			};
 			seed.deserialize(&mut DeParser {
-				data: &mut inner.clone()[0]
+				data,
+				strict: self.inner.strict,
+				paths: self.inner.paths,
 			})
 		}else {
 			unreachable!()
@@ -746,211 +1735,141 @@ impl<'de, 'a> VariantAccess<'de> for DeEnum<'a> {
 	where
 		V: Visitor<'de>,
 	{
-		if let DataEnum::Enum(_, inner) = &self.inner.data.data {
+		if let DataEnum::Enum(_, inner) = &mut self.inner.data.data {
 			DeParser {
 				data: &mut ParsedData {
-					data: DataEnum::Node(inner.clone()),
+					data: DataEnum::Node(core::mem::take(inner)),
 					..Default::default()
-				}
+				},
+				strict: self.inner.strict,
+				paths: self.inner.paths,
 			}.deserialize_seq(input)
 		}else {
 			unreachable!()
 		}
 	}
 
-	fn struct_variant<V>(self, _fields: &'static [&'static str], input: V) -> Result<V::Value, Error>
+	fn struct_variant<V>(self, fields: &'static [&'static str], input: V) -> Result<V::Value, Error>
 	where
 		V: Visitor<'de>,
 	{
-		if let DataEnum::Enum(_, inner) = &self.inner.data.data {
+		if let DataEnum::Enum(_, inner) = &mut self.inner.data.data {
 			DeParser {
 				data: &mut ParsedData {
-					data: DataEnum::Node(inner.clone()),
+					data: DataEnum::Node(core::mem::take(inner)),
 					..Default::default()
-				}
-			}.deserialize_any(input)
+				},
+				strict: self.inner.strict,
+				paths: self.inner.paths,
+			}.deserialize_struct("", fields, input)
 		}else {
 			unreachable!()
 		}
 	}
 }
 
-pub trait CanBeAnimated<'a, T> where
-	T: serde::Serialize + serde::Deserialize<'a>
-{
-	fn get_animation_map(&mut self) -> &mut HashMap<String, Animation>;
-	fn get_animate_target(&mut self) -> &mut T;
+/// the key of a map entry that only has a name, like the fields from `from_json`, or a string key. the name is parsed when a int, float, bool or char key is expected
+struct NameKey(String);
 
-	fn caculate(&mut self, duration: &Duration) -> Result<(), Error> {
-		let map = self.get_animation_map().clone();
-		if map.is_empty() {
-			return Ok(())
+macro_rules! deserialize_name {
+	($i1: ident, $i2: ident, $t: ty) => {
+		fn $i1<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
+			match self.0.parse::<$t>() {
+				Ok(t) => input.$i2(t),
+				Err(_) => Err(Error::UnexpectedType(stringify!($t).to_string(), "string")),
+			}
 		}
-		let target = self.get_animate_target();
-		let mut parsed_data = to_data(target)?;
-		animation_caculate(&String::new(), &mut parsed_data, duration, &map);
-		*target = from_data(&mut parsed_data)?;
+	};
+}
 
-		Ok(())
+impl<'de> Deserializer<'de> for NameKey {
+	type Error = Error;
+
+	fn deserialize_any<V: Visitor<'de>>(self, input: V) -> Result<V::Value, Error> {
+		input.visit_string(self.0)
 	}
-}
 
-fn animation_caculate(id: &String, data: &mut ParsedData, duration: &Duration, map: &HashMap<String, Animation>) {
-	let id = format!("{}----{}", id, data.name);
-	match &mut data.data {
-		DataEnum::Node(inner) => {
-			for inside in inner {
-				animation_caculate(&id, inside, duration, map);
-			}
-		},
-		DataEnum::Map(box_inside) => {
-			let (key, mut inner) = *box_inside.clone();
-			animation_caculate(&id, &mut inner, duration, map);
-			*box_inside = Box::new((key, inner));
-		},
-		DataEnum::Enum(_, inner) => {
-			for inside in inner {
-				animation_caculate(&id, inside, duration, map);
-			}
-		},
-		DataEnum::Int(value, range) => {
-			if let Some(t) = map.get(&id) {
-				if let Some(x) = t.caculate(duration) {
-					let x = x as i128;
-					let compress = if x > *range.end() {
-						*range.end()
-					}else if x < *range.start(){
-						*range.start()
-					}else {
-						x
-					};
-					*value = compress;
-				}else if duration > &t.len() && !t.is_empty() {
-					let x = t.end_value() as i128;
-					let compress = if x > *range.end() {
-						*range.end()
-					}else if x < *range.start() {
-						*range.start()
-					}else {
-						x
-					};
-					*value = compress
-				}else if duration < &t.start_time && !t.is_empty() {
-					let x = t.start_value as i128;
-					let compress = if x > *range.end() {
-						*range.end()
-					}else if x < *range.start() {
-						*range.start()
-					}else {
-						x
-					};
-					*value = compress;
-				}
-			}
-		},
-		DataEnum::Float(value) => {
-			if let Some(t) = map.get(&id) {
-				if let Some(x) = t.caculate(duration) {
-					*value = x as f64;
-				}else if duration > &(t.len() + t.start_time) && !t.is_empty() {
-					let x = t.end_value() as f64;
-					*value = x
-				}else if duration < &t.start_time && !t.is_empty() {
-					*value = t.start_value as f64
-				}
-			}
-		},
-		_ => {}
+	deserialize_name!(deserialize_bool, visit_bool, bool);
+	deserialize_name!(deserialize_i8, visit_i8, i8);
+	deserialize_name!(deserialize_i16, visit_i16, i16);
+	deserialize_name!(deserialize_i32, visit_i32, i32);
+	deserialize_name!(deserialize_i64, visit_i64, i64);
+	deserialize_name!(deserialize_i128, visit_i128, i128);
+	deserialize_name!(deserialize_u8, visit_u8, u8);
+	deserialize_name!(deserialize_u16, visit_u16, u16);
+	deserialize_name!(deserialize_u32, visit_u32, u32);
+	deserialize_name!(deserialize_u64, visit_u64, u64);
+	deserialize_name!(deserialize_u128, visit_u128, u128);
+	deserialize_name!(deserialize_f32, visit_f32, f32);
+	deserialize_name!(deserialize_f64, visit_f64, f64);
+	deserialize_name!(deserialize_char, visit_char, char);
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, input: V) -> Result<V::Value, Error> {
+		input.visit_newtype_struct(self)
 	}
+
+	fn deserialize_enum<V: Visitor<'de>>(self, _: &'static str, _: &'static [&'static str], input: V) -> Result<V::Value, Error> {
+		input.visit_enum(self.0.into_deserializer())
+	}
+
+	serde::forward_to_deserialize_any! {
+		str string bytes byte_buf option unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+	}
+}
+
+/// what goes before every name in a id, see [`field_id`]
+pub const ID_SEPARATOR: &str = "----";
+
+/// build the id of a field used as the key of animation maps and delta maps.
+///
+/// `path` starts from the root value, whose name is the name of your struct (or empty for values without a name), then the names of every field down to the target.
+/// for example, field `b` inside field `a` of `struct Foo` is `field_id(&["Foo", "a", "b"])`, which gives `----Foo----a----b`.
+///
+/// names that contain [`ID_SEPARATOR`] or `%`, or start or end with `-`, could make two different paths give the same id, so their `%` and `-` are written as `%25` and `%2D`.
+/// other names, which are nearly all of them, are kept as they are
+pub fn field_id(path: &[&str]) -> String {
+	path.iter().fold(String::new(), |id, name| join_id(&id, name))
 }
 
-/// find difference for two structs, only avaluable for numeric fields. outputs left - right
-pub fn caculate_delta<T: Serialize>(left: &T, right: &T) -> Result<HashMap<String, f64>, Error> {
-	let left = to_data(left)?;
-	let right = to_data(right)?;
-	let mut map = HashMap::new();
-	caculate_delta_data(left, right, &mut map, String::new());
-	Ok(map)
+fn join_id(id: &str, name: &str) -> String {
+	format!("{}{}{}", id, ID_SEPARATOR, escape_name(name))
 }
 
-/// find difference for two structs, only avaluable for numeric fields. outputs left - right
-pub fn apply_delta<'a, T: Serialize+ Deserialize<'a>>(input: &mut T, delta_map: &HashMap<String, f64>) -> Result<(), Error> {
-	if delta_map.is_empty() {
-		return Ok(());
+fn escape_name(name: &str) -> Cow<'_, str> {
+	if name.contains(ID_SEPARATOR) || name.contains('%') || name.starts_with('-') || name.ends_with('-') {
+		Cow::Owned(name.replace('%', "%25").replace('-', "%2D"))
+	}else {
+		Cow::Borrowed(name)
 	}
-	let mut data = to_data(input)?;
-	apply_delta_data(&String::new(), &mut data, delta_map);
-	*input = from_data(&mut data)?;
-	Ok(())
 }
 
-fn apply_delta_data(id: &String, data: &mut ParsedData, map: &HashMap<String, f64>) {
-	let id = format!("{}----{}", id, data.name);
-	match &mut data.data {
-		DataEnum::Node(inner) => {
-			for inside in inner {
-				apply_delta_data(&id, inside, map);
-			}
-		},
-		DataEnum::Map(box_inside) => {
-			let (key, mut inner) = *box_inside.clone();
-			apply_delta_data(&id, &mut inner, map);
-			*box_inside = Box::new((key, inner));
-		},
-		DataEnum::Enum(_, inner) => {
-			for inside in inner {
-				apply_delta_data(&id, inside, map);
-			}
-		},
-		DataEnum::Int(value, range) => {
-			if let Some(t) = map.get(&id) {
-				let x = *t as i128 + *value;
-				let compress = if x > *range.end() {
-					*range.end()
-				}else if x < *range.start(){
-					*range.start()
-				}else {
-					x
-				};
-				*value = compress;
-			}
-		},
-		DataEnum::Float(value) => {
-			if let Some(t) = map.get(&id) {
-					*value += *t;
-			}
-		},
-		_ => {}
+/// reverse of [`escape_name`]. `%2D` goes first, as `%25` may be followed by `2D` but no `%2D` can be part of a `%25`
+fn unescape_name(name: &str) -> Cow<'_, str> {
+	if name.contains('%') {
+		Cow::Owned(name.replace("%2D", "-").replace("%25", "%"))
+	}else {
+		Cow::Borrowed(name)
 	}
 }
 
-fn caculate_delta_data(left: ParsedData, right: ParsedData, map: &mut HashMap<String, f64>, id: String){
-	let id = format!("{}----{}", id, left.name);
-	match (left.data, right.data) {
-		(DataEnum::Node(linner), DataEnum::Node(rinner))=> {
-			for (linside, rinside) in linner.into_iter().zip(rinner.into_iter()) {
-				caculate_delta_data(linside, rinside, map, id.clone());
-			}
-		},
-		(DataEnum::Map(lbox_inside), DataEnum::Map(rbox_inside),) => {
-			let ((_, linner), (_, rinner)) = (*lbox_inside, *rbox_inside);
-			caculate_delta_data(linner, rinner, map, id);
-		},
-		(DataEnum::Enum(_, linner), DataEnum::Enum(_, rinner)) => {
-			for (linside, rinside) in linner.into_iter().zip(rinner.into_iter()) {
-				caculate_delta_data(linside, rinside, map, id.clone());
-			}
-		},
-		(DataEnum::Int(lvalue, _), DataEnum::Int(rvalue, _)) => {
-			if lvalue != rvalue {
-				map.insert(id, (lvalue - rvalue) as f64);
-			}
-		},
-		(DataEnum::Float(lvalue), DataEnum::Float(rvalue)) => {
-			if lvalue != rvalue {
-				map.insert(id, lvalue - rvalue);
-			}
-		},
-		_ => {}
+/// split a id into the names it was joined from, see [`field_id`]. returns `None` if `id` does not start with [`ID_SEPARATOR`]
+fn split_id(id: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+	Some(id.strip_prefix(ID_SEPARATOR)?.split(ID_SEPARATOR).map(unescape_name))
+}
+
+/// reversed ranges are treated as if they were swapped
+#[cfg(feature = "std")]
+fn clamp<T: PartialOrd + Copy>(x: T, range: &RangeInclusive<T>) -> T {
+	let (start, end) = if range.start() <= range.end() {
+		(*range.start(), *range.end())
+	}else {
+		(*range.end(), *range.start())
+	};
+	if x > end {
+		end
+	}else if x < start {
+		start
+	}else {
+		x
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,72 @@
+//! store [`time::Duration`] and [`time::OffsetDateTime`] fields as a single [`DataEnum::Float`](crate::DataEnum::Float) so animations and deltas can drive them, needs `std` feature.
+//!
+//! by default `time` serializes them as strings or tuples, which can not be animated. use these modules on your fields instead:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Fade {
+//!     #[serde(with = "nablo_data::time_float::duration")]
+//!     delay: time::Duration,
+//!     #[serde(with = "nablo_data::time_float::offset_date_time")]
+//!     started: time::OffsetDateTime,
+//! }
+//! ```
+//!
+//! both are stored as seconds in `f64`, so precision below about a microsecond may be lost.
+//!
+//! `std::time::Duration` fields already annotated with `serde_with` need no module of this crate, `DurationSecondsWithFrac<f64>` also writes a single `f64` and lands as one [`DataEnum::Float`](crate::DataEnum::Float).
+//! `DurationSeconds<u64>` lands as a [`DataEnum::Int`](crate::DataEnum::Int) of whole seconds, which can be animated too but is rounded by [`IntRounding`](crate::IntRounding):
+//!
+//! ```ignore
+//! #[serde_as]
+//! #[derive(Serialize, Deserialize)]
+//! struct Fade {
+//!     #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+//!     delay: std::time::Duration,
+//! }
+//! ```
+//!
+//! negative seconds fail to deserialize into `std::time::Duration`, so clamp animations that may go below zero with [`CanBeAnimated::get_float_range_map`](crate::CanBeAnimated::get_float_range_map).
+
+/// a [`time::Duration`] as seconds
+pub mod duration {
+	use serde::de::Error;
+	use serde::Deserialize;
+	use serde::Deserializer;
+	use serde::Serializer;
+	use time::Duration;
+
+	/// serialize a `Duration` as seconds
+	pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_f64(value.as_seconds_f64())
+	}
+
+	/// deserialize seconds into a `Duration`, NaN or seconds out of the range of `Duration` is an error
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+		let seconds = f64::deserialize(deserializer)?;
+		Duration::checked_seconds_f64(seconds).ok_or_else(|| D::Error::custom(format!("{} seconds is not a valid duration", seconds)))
+	}
+}
+
+/// a [`time::OffsetDateTime`] as seconds since the unix epoch, the offset is not stored and deserialized values are always in utc
+pub mod offset_date_time {
+	use serde::de::Error;
+	use serde::Deserialize;
+	use serde::Deserializer;
+	use serde::Serializer;
+	use time::OffsetDateTime;
+
+	/// serialize a `OffsetDateTime` as seconds since the unix epoch
+	pub fn serialize<S: Serializer>(value: &OffsetDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_f64(value.unix_timestamp_nanos() as f64 / 1e9)
+	}
+
+	/// deserialize seconds since the unix epoch into a `OffsetDateTime` in utc, NaN or seconds out of the range of `OffsetDateTime` is an error
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OffsetDateTime, D::Error> {
+		let seconds = f64::deserialize(deserializer)?;
+		if !seconds.is_finite() {
+			return Err(D::Error::custom(format!("{} is not a valid timestamp", seconds)));
+		}
+		OffsetDateTime::from_unix_timestamp_nanos((seconds * 1e9).round() as i128).map_err(D::Error::custom)
+	}
+}
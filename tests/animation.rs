@@ -0,0 +1,412 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use common::Animated;
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use time::Duration;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Position {
+	x: f32,
+	y: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Player {
+	position: Position,
+	colors: Vec<f64>,
+}
+
+fn player() -> Player {
+	Player {
+		position: Position { x: 0.0, y: 0.0 },
+		colors: vec![0.0, 0.0, 0.0],
+	}
+}
+
+#[test]
+fn field_id_matches_the_animated_field() {
+	assert_eq!(field_id(&["Player", "position", "x"]), "----Player----position----x");
+	let map = HashMap::from([
+		(field_id(&["Player", "position", "y"]), common::linear(0.0, 2.0, 1)),
+		(field_id(&["Player", "colors", "1"]), common::linear(0.0, 4.0, 1)),
+	]);
+	let mut animated = Animated::new(player(), map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.position, Position { x: 0.0, y: 2.0 });
+	assert_eq!(animated.target.colors, [0.0, 4.0, 0.0]);
+}
+
+#[test]
+fn field_id_matches_leaves() {
+	let data = to_data(&player()).unwrap();
+	let ids: Vec<String> = data.leaves().map(|(id, _)| id).collect();
+	assert_eq!(ids[..2], [field_id(&["Player", "position", "x"]), field_id(&["Player", "position", "y"])]);
+}
+
+#[test]
+fn float_is_clamped_into_its_range() {
+	let id = field_id(&["Player", "position", "x"]);
+	let mut animated = Animated::new(player(), HashMap::from([(id.clone(), common::linear(-10.0, 10.0, 2))]));
+	animated.ranges.insert(id, -1.0..=1.0);
+	let mut frames = vec![];
+	for second in 0..=2 {
+		animated.caculate(&Duration::seconds(second)).unwrap();
+		frames.push(animated.target.position.x);
+	}
+	assert_eq!(frames, [-1.0, 0.0, 1.0]);
+}
+
+#[test]
+fn reversed_range_clamps_like_a_swapped_one() {
+	let id = field_id(&["Player", "position", "x"]);
+	let mut animated = Animated::new(player(), HashMap::from([(id.clone(), common::linear(-10.0, 10.0, 2))]));
+	animated.ranges.insert(id, RangeInclusive::new(1.0, -1.0));
+	let mut frames = vec![];
+	for second in 0..=2 {
+		animated.caculate(&Duration::seconds(second)).unwrap();
+		frames.push(animated.target.position.x);
+	}
+	assert_eq!(frames, [-1.0, 0.0, 1.0]);
+}
+
+#[test]
+fn float_without_range_is_not_clamped() {
+	let mut animated = Animated::new(player(), HashMap::from([(field_id(&["Player", "position", "x"]), common::linear(-10.0, 10.0, 2))]));
+	animated.caculate(&Duration::seconds(2)).unwrap();
+	assert_eq!(animated.target.position.x, 10.0);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Scores {
+	by_name: std::collections::BTreeMap<String, f32>,
+}
+
+#[test]
+fn map_values_are_animated_in_place() {
+	let scores = Scores { by_name: [("a".to_string(), 0.0), ("b".to_string(), 0.0), ("c".to_string(), 0.0)].into() };
+	let map = HashMap::from([
+		(field_id(&["Scores", "by_name", "a"]), common::linear(0.0, 2.0, 2)),
+		(field_id(&["Scores", "by_name", "c", ""]), common::linear(0.0, 4.0, 2)),
+	]);
+	let mut animated = Animated::new(scores, map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.by_name, [("a".to_string(), 1.0), ("b".to_string(), 0.0), ("c".to_string(), 2.0)].into());
+}
+
+#[test]
+fn builder_keys_animate_the_right_leaves() {
+	let map = AnimationMapBuilder::new("Player")
+		.field("position").field("x").animation(common::linear(0.0, 2.0, 1))
+		.field("colors").index(2).animation(common::linear(0.0, 3.0, 1))
+		.build();
+	assert_eq!(map.len(), 2);
+	let mut animated = Animated::new(player(), map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.position, Position { x: 2.0, y: 0.0 });
+	assert_eq!(animated.target.colors, [0.0, 0.0, 3.0]);
+}
+
+#[test]
+fn builder_key_of_a_struct_animates_every_field() {
+	let map = AnimationMapBuilder::new("Player").field("position").animation(common::linear(0.0, 2.0, 1)).build();
+	let mut animated = Animated::new(player(), map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.position, Position { x: 2.0, y: 2.0 });
+	assert_eq!(animated.target.colors, [0.0, 0.0, 0.0]);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Counter {
+	count: i32,
+	speed: f32,
+}
+
+#[test]
+fn sample_field_samples_ints_and_floats() {
+	let map = AnimationMapBuilder::new("Counter").field("count").animation(common::linear(0.0, 10.0, 1)).field("speed").animation(common::linear(0.0, 10.0, 1)).build();
+	let target = Counter { count: 0, speed: 0.0 };
+	let times = [Duration::ZERO, Duration::milliseconds(500), Duration::seconds(2)];
+	let range = i32::MIN as i128..=i32::MAX as i128;
+	assert_eq!(sample_field(&target, &map, "count", &times).unwrap(), [DataEnum::Int(0, range.clone()), DataEnum::Int(5, range.clone()), DataEnum::Int(10, range)]);
+	assert_eq!(sample_field(&target, &map, "speed", &times).unwrap(), [DataEnum::Float(0.0), DataEnum::Float(5.0), DataEnum::Float(10.0)]);
+	assert_eq!(target, Counter { count: 0, speed: 0.0 });
+}
+
+#[test]
+fn sample_field_of_missing_field_is_an_error() {
+	let map = AnimationMapBuilder::new("Counter").field("count").animation(common::linear(0.0, 10.0, 1)).build();
+	assert!(matches!(sample_field(&Counter { count: 0, speed: 0.0 }, &map, "missing", &[Duration::ZERO]), Err(Error::UnknownField(_))));
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum State {
+	Idle,
+	Running,
+	Jumping { height: f32 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Actor {
+	state: State,
+}
+
+#[test]
+fn variants_switch_at_their_time() {
+	let mut animated = Animated::new(Actor { state: State::Idle }, HashMap::new());
+	animated.variants.insert(field_id(&["Actor", "state"]), vec![
+		(Duration::seconds(1), to_data(&State::Running).unwrap().data),
+		(Duration::seconds(2), to_data(&State::Jumping { height: 2.0 }).unwrap().data),
+	]);
+	let mut states = vec![];
+	for millis in [0, 999, 1000, 1500, 2500] {
+		animated.caculate(&Duration::milliseconds(millis)).unwrap();
+		states.push(animated.target.state.clone());
+	}
+	assert_eq!(states, [State::Idle, State::Idle, State::Running, State::Running, State::Jumping { height: 2.0 }]);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.state, State::Running);
+}
+
+#[test]
+fn switched_variant_values_can_be_animated() {
+	let mut animated = Animated::new(Actor { state: State::Idle }, AnimationMapBuilder::new("Actor").field("state").field("height").animation(common::linear(0.0, 4.0, 4)).build());
+	animated.variants.insert(field_id(&["Actor", "state"]), vec![(Duration::seconds(1), to_data(&State::Jumping { height: 0.0 }).unwrap().data)]);
+	animated.caculate(&Duration::seconds(2)).unwrap();
+	assert_eq!(animated.target.state, State::Jumping { height: 2.0 });
+}
+
+#[test]
+fn ints_and_floats_end_at_the_same_moment() {
+	let map = AnimationMapBuilder::new("Counter").animation(common::delayed(2, 0.0, 10.0, 2)).build();
+	let mut animated = Animated::new(Counter { count: -1, speed: -1.0 }, map);
+	let mut frames = vec![];
+	for millis in [0, 1000, 2000, 3000, 3999, 4000, 4001, 9000] {
+		animated.caculate(&Duration::milliseconds(millis)).unwrap();
+		frames.push((animated.target.count, animated.target.speed));
+	}
+	assert_eq!(frames, [(0, 0.0), (0, 0.0), (0, 0.0), (5, 5.0), (9, 9.995), (10, 10.0), (10, 10.0), (10, 10.0)]);
+}
+
+fn rounded_frames(rounding: IntRounding, to: f32) -> Vec<i32> {
+	let id = field_id(&["Counter", "count"]);
+	let mut animated = Animated::new(Counter { count: 0, speed: 0.0 }, HashMap::from([(id.clone(), common::linear(0.0, to, 1))]));
+	animated.roundings.insert(id, rounding);
+	[0, 125, 250, 375, 500, 625, 1000].into_iter().map(|millis| {
+		animated.caculate(&Duration::milliseconds(millis)).unwrap();
+		animated.target.count
+	}).collect()
+}
+
+#[test]
+fn int_rounding_modes() {
+	assert_eq!(rounded_frames(IntRounding::Trunc, 10.0), [0, 1, 2, 3, 5, 6, 10]);
+	assert_eq!(rounded_frames(IntRounding::Nearest, 10.0), [0, 1, 3, 4, 5, 6, 10]);
+	assert_eq!(rounded_frames(IntRounding::Floor, 10.0), [0, 1, 2, 3, 5, 6, 10]);
+	assert_eq!(rounded_frames(IntRounding::Ceil, 10.0), [0, 2, 3, 4, 5, 7, 10]);
+	assert_eq!(rounded_frames(IntRounding::Stepped, 10.0), [0, 1, 3, 3, 5, 6, 10]);
+}
+
+#[test]
+fn int_rounding_of_negative_values() {
+	assert_eq!(rounded_frames(IntRounding::Trunc, -10.0), [0, -1, -2, -3, -5, -6, -10]);
+	assert_eq!(rounded_frames(IntRounding::Floor, -10.0), [0, -2, -3, -4, -5, -7, -10]);
+	assert_eq!(rounded_frames(IntRounding::Ceil, -10.0), [0, -1, -2, -3, -5, -6, -10]);
+}
+
+#[test]
+fn int_rounding_defaults_to_trunc() {
+	let mut animated = Animated::new(Counter { count: 0, speed: 0.0 }, HashMap::from([(field_id(&["Counter", "count"]), common::linear(0.0, 10.0, 1))]));
+	animated.caculate(&Duration::milliseconds(375)).unwrap();
+	assert_eq!(animated.target.count, 3);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Vector {
+	x: f32,
+	y: f64,
+	z: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Body {
+	position: Vector,
+	name: String,
+}
+
+#[test]
+fn one_group_animates_every_component() {
+	let map = HashMap::from([(field_id(&["Body", "position"]), common::linear(0.0, 4.0, 2))]);
+	let mut animated = Animated::new(Body { position: Vector { x: 0.0, y: 0.0, z: 0 }, name: "body".to_string() }, map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.position, Vector { x: 2.0, y: 2.0, z: 2 });
+	animated.caculate(&Duration::seconds(2)).unwrap();
+	assert_eq!(animated.target.position, Vector { x: 4.0, y: 4.0, z: 4 });
+	assert_eq!(animated.target.name, "body");
+}
+
+#[test]
+fn own_animation_wins_over_the_group() {
+	let map = HashMap::from([
+		(field_id(&["Body", "position"]), common::linear(0.0, 4.0, 2)),
+		(field_id(&["Body", "position", "y"]), common::linear(10.0, 20.0, 2)),
+	]);
+	let mut animated = Animated::new(Body { position: Vector { x: 0.0, y: 0.0, z: 0 }, name: "body".to_string() }, map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.position, Vector { x: 2.0, y: 15.0, z: 2 });
+}
+
+#[test]
+fn caculate_to_leaves_the_target_untouched() {
+	let map = HashMap::from([(field_id(&["Body", "position", "x"]), common::linear(0.0, 4.0, 2))]);
+	let original = Body { position: Vector { x: 0.0, y: 1.0, z: 2 }, name: "body".to_string() };
+	let mut animated = Animated::new(original.clone(), map);
+	let value = animated.caculate_to(&Duration::seconds(1)).unwrap();
+	assert_eq!(value.position, Vector { x: 2.0, ..original.position.clone() });
+	assert_eq!(animated.target, original);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target, value);
+}
+
+#[test]
+fn caculate_to_without_matches_returns_the_target() {
+	let map = HashMap::from([(field_id(&["Body", "missing"]), common::linear(0.0, 4.0, 2))]);
+	let original = Body { position: Vector { x: 0.0, y: 1.0, z: 2 }, name: "body".to_string() };
+	let mut animated = Animated::new(original.clone(), map);
+	assert_eq!(animated.caculate_to(&Duration::seconds(1)).unwrap(), original);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+struct Widget {
+	size: u16,
+	label: String,
+	visible: bool,
+	offset: Vector2,
+	children: Vec<f32>,
+	hint: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+struct Vector2 {
+	x: f32,
+	y: i64,
+}
+
+#[test]
+fn animatable_fields_lists_numeric_leaves() {
+	assert_eq!(animatable_fields::<Widget>().unwrap(), [
+		field_id(&["Widget", "size"]),
+		field_id(&["Widget", "offset", "x"]),
+		field_id(&["Widget", "offset", "y"]),
+	]);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Transform {
+	scale: [f32; 3],
+	size: [f32; 2],
+}
+
+#[test]
+fn arrays_share_one_animation_and_read_back() {
+	let map = AnimationMapBuilder::new("Transform")
+		.field("scale").animation(common::linear(1.0, 3.0, 2))
+		.field("size").index(1).animation(common::linear(0.0, 10.0, 2))
+		.build();
+	let mut animated = Animated::new(Transform { scale: [1.0, 1.0, 1.0], size: [5.0, 0.0] }, map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target, Transform { scale: [2.0, 2.0, 2.0], size: [5.0, 5.0] });
+	animated.caculate(&Duration::seconds(2)).unwrap();
+	assert_eq!(animated.target, Transform { scale: [3.0, 3.0, 3.0], size: [5.0, 10.0] });
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Modes {
+	once: f32,
+	looped: f32,
+	ping: f32,
+}
+
+fn modes() -> Animated<Modes> {
+	let map = AnimationMapBuilder::new("Modes")
+		.field("once").animation(common::delayed(1, 0.0, 4.0, 4))
+		.field("looped").animation(common::delayed(1, 0.0, 4.0, 4))
+		.field("ping").animation(common::delayed(1, 0.0, 4.0, 4))
+		.build();
+	let mut animated = Animated::new(Modes { once: 0.0, looped: 0.0, ping: 0.0 }, map);
+	animated.modes = HashMap::from([(field_id(&["Modes", "looped"]), PlayMode::Loop), (field_id(&["Modes", "ping"]), PlayMode::PingPong)]);
+	animated
+}
+
+#[test]
+fn play_modes_over_two_cycles() {
+	let mut animated = modes();
+	let mut at = |seconds: f64| {
+		animated.caculate(&Duration::seconds_f64(seconds)).unwrap();
+		animated.target.clone()
+	};
+	let values = |once, looped, ping| Modes { once, looped, ping };
+	assert_eq!(at(0.0), values(0.0, 0.0, 0.0));
+	assert_eq!(at(2.0), values(1.0, 1.0, 1.0));
+	assert_eq!(at(4.0), values(3.0, 3.0, 3.0));
+	assert_eq!(at(6.0), values(4.0, 1.0, 3.0));
+	assert_eq!(at(8.0), values(4.0, 3.0, 1.0));
+	assert_eq!(at(9.0), values(4.0, 0.0, 0.0));
+	assert_eq!(at(11.0), values(4.0, 2.0, 2.0));
+	assert_eq!(at(15.0), values(4.0, 2.0, 2.0));
+	assert_eq!(at(16.0), values(4.0, 3.0, 1.0));
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Huge {
+	near: i64,
+	far: i64,
+	wide: i128,
+}
+
+#[test]
+fn large_ints_keep_their_value_and_clamp() {
+	let hold = |value: f32| common::linear(value, value, 1);
+	let map = AnimationMapBuilder::new("Huge")
+		.field("near").animation(hold(i64::MAX as f32))
+		.field("far").animation(hold(1e30))
+		.field("wide").animation(hold(-1e38))
+		.build();
+	let mut animated = Animated::new(Huge { near: i64::MAX - 3, far: 0, wide: 0 }, map);
+	animated.roundings = HashMap::from([(field_id(&["Huge", "near"]), IntRounding::Nearest)]);
+	animated.caculate(&Duration::milliseconds(500)).unwrap();
+	assert_eq!(animated.target, Huge { near: i64::MAX - 3, far: i64::MAX, wide: -1e38f32 as i128 });
+}
+
+#[test]
+fn advance_all_updates_every_target() {
+	let x = field_id(&["Player", "position", "x"]);
+	let shared = HashMap::from([(x.clone(), common::linear(0.0, 2.0, 2))]);
+	let mut first = Animated::new(player(), shared.clone());
+	let mut second = Animated::new(player(), shared);
+	let mut third = Animated::new(player(), HashMap::from([(field_id(&["Player", "colors", "2"]), common::linear(1.0, 3.0, 1))]));
+	let mut targets: [&mut dyn CanBeAnimated<'_, Player>; 3] = [&mut first, &mut second, &mut third];
+	// the first two share a group, so the value of their animation is computed once
+	assert_eq!(advance_all(&mut targets, &Duration::seconds(1)).unwrap(), 2);
+	assert_eq!(first.target.position, Position { x: 1.0, y: 0.0 });
+	assert_eq!(second.target, first.target);
+	assert_eq!(third.target, Player { colors: vec![0.0, 0.0, 3.0], ..player() });
+}
+
+#[test]
+fn advance_all_keeps_play_modes_of_a_group() {
+	let x = field_id(&["Player", "position", "x"]);
+	let map = HashMap::from([(x.clone(), common::linear(0.0, 2.0, 2))]);
+	let mut once = Animated::new(player(), map.clone());
+	let mut looped = Animated::new(player(), map);
+	looped.modes.insert(x, PlayMode::Loop);
+	let mut targets: [&mut dyn CanBeAnimated<'_, Player>; 2] = [&mut once, &mut looped];
+	assert_eq!(advance_all(&mut targets, &Duration::seconds(3)).unwrap(), 1);
+	assert_eq!(once.target.position.x, 2.0);
+	assert_eq!(looped.target.position.x, 1.0);
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use nablo_data::*;
+use nablo_shape::prelude::Animation;
+use serde::Deserialize;
+use serde::Serialize;
+use serde::Serializer;
+use std::cell::Cell;
+use std::collections::HashMap;
+use time::Duration;
+
+thread_local! {
+	static SERIALIZED: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+enum Shape {
+	Dot,
+	Circle(f32),
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+struct Target {
+	x: f32,
+	shape: Shape,
+}
+
+impl Serialize for Target {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeStruct;
+		SERIALIZED.with(|count| count.set(count.get() + 1));
+		let mut output = serializer.serialize_struct("Target", 2)?;
+		output.serialize_field("x", &self.x)?;
+		output.serialize_field("shape", &self.shape)?;
+		output.end()
+	}
+}
+
+struct Animated {
+	target: Target,
+	map: HashMap<String, Animation>,
+	variants: HashMap<String, Vec<(Duration, DataEnum)>>,
+	cache: Option<MatchCache>,
+}
+
+impl CanBeAnimated<'_, Target> for Animated {
+	fn get_animation_map(&mut self) -> &mut HashMap<String, Animation> {
+		&mut self.map
+	}
+
+	fn get_animate_target(&mut self) -> &mut Target {
+		&mut self.target
+	}
+
+	fn get_variant_map(&self) -> Option<&HashMap<String, Vec<(Duration, DataEnum)>>> {
+		Some(&self.variants)
+	}
+
+	fn get_match_cache(&mut self) -> Option<&mut MatchCache> {
+		self.cache.as_mut()
+	}
+}
+
+fn animated(cache: bool) -> Animated {
+	Animated {
+		target: Target { x: 0.0, shape: Shape::Dot },
+		map: AnimationMapBuilder::new("Target").field("missing").animation(common::linear(0.0, 1.0, 1)).build(),
+		variants: HashMap::new(),
+		cache: cache.then(MatchCache::default),
+	}
+}
+
+fn serialized_over_frames(animated: &mut Animated, frames: i64) -> usize {
+	let before = SERIALIZED.with(Cell::get);
+	for frame in 0..frames {
+		animated.caculate(&Duration::milliseconds(frame * 100)).unwrap();
+	}
+	SERIALIZED.with(Cell::get) - before
+}
+
+#[test]
+fn unmatched_ids_are_serialized_once_with_a_cache() {
+	assert_eq!(serialized_over_frames(&mut animated(false), 10), 10);
+	assert_eq!(serialized_over_frames(&mut animated(true), 10), 1);
+}
+
+#[test]
+fn changed_ids_invalidate_the_cache() {
+	let mut animated = animated(true);
+	assert_eq!(serialized_over_frames(&mut animated, 3), 1);
+	animated.map.insert(field_id(&["Target", "x"]), common::linear(0.0, 1.0, 1));
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.x, 1.0);
+}
+
+#[test]
+fn cleared_cache_serializes_again() {
+	let mut animated = animated(true);
+	assert_eq!(serialized_over_frames(&mut animated, 3), 1);
+	animated.cache.as_mut().unwrap().clear();
+	assert_eq!(serialized_over_frames(&mut animated, 3), 1);
+}
+
+#[test]
+fn variant_switch_is_not_skipped_before_its_time() {
+	let mut animated = animated(true);
+	animated.map.clear();
+	let circle = to_data(&Shape::Circle(2.0)).unwrap().data;
+	animated.variants.insert(field_id(&["Target", "shape"]), vec![(Duration::seconds(2), circle)]);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target.shape, Shape::Dot);
+	animated.caculate(&Duration::seconds(3)).unwrap();
+	assert_eq!(animated.target.shape, Shape::Circle(2.0));
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use nablo_data::*;
+use nablo_shape::prelude::Animation;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use time::Duration;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+enum Mode {
+	Idle,
+	Busy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Pair {
+	a: f32,
+	b: f32,
+	mode: Mode,
+}
+
+struct Animated {
+	pair: Pair,
+	map: HashMap<String, Animation>,
+	variants: HashMap<String, Vec<(Duration, DataEnum)>>,
+}
+
+impl CanBeAnimated<'_, Pair> for Animated {
+	fn get_animation_map(&mut self) -> &mut HashMap<String, Animation> {
+		&mut self.map
+	}
+
+	fn get_animate_target(&mut self) -> &mut Pair {
+		&mut self.pair
+	}
+
+	fn get_variant_map(&self) -> Option<&HashMap<String, Vec<(Duration, DataEnum)>>> {
+		Some(&self.variants)
+	}
+}
+
+fn animated() -> Animated {
+	// `a` runs from 0s to 4s and `b` from 2s to 4s, so the timeline is 0s to 4s
+	Animated {
+		pair: Pair { a: -1.0, b: -1.0, mode: Mode::Idle },
+		map: AnimationMapBuilder::new("Pair").field("a").animation(common::linear(0.0, 4.0, 4)).field("b").animation(common::delayed(2, 0.0, 2.0, 2)).build(),
+		variants: HashMap::new(),
+	}
+}
+
+fn at(animated: &mut Animated, progress: f64) -> (f32, f32) {
+	animated.caculate_progress(progress).unwrap();
+	(animated.pair.a, animated.pair.b)
+}
+
+#[test]
+fn animations_of_different_lengths_share_one_timeline() {
+	let mut animated = animated();
+	assert_eq!(at(&mut animated, 0.0), (0.0, 0.0));
+	assert_eq!(at(&mut animated, 0.5), (2.0, 0.0));
+	assert_eq!(at(&mut animated, 0.75), (3.0, 1.0));
+	assert_eq!(at(&mut animated, 1.0), (4.0, 2.0));
+}
+
+#[test]
+fn progress_is_clamped() {
+	let mut animated = animated();
+	assert_eq!(at(&mut animated, 7.0), (4.0, 2.0));
+	assert_eq!(at(&mut animated, -1.0), (0.0, 0.0));
+	assert_eq!(at(&mut animated, f64::NAN), (0.0, 0.0));
+}
+
+#[test]
+fn variant_switches_extend_the_timeline() {
+	let mut animated = animated();
+	animated.variants.insert(field_id(&["Pair", "mode"]), vec![(Duration::seconds(8), to_data(&Mode::Busy).unwrap().data)]);
+	assert_eq!(at(&mut animated, 0.5), (4.0, 2.0));
+	assert_eq!(animated.pair.mode, Mode::Idle);
+	at(&mut animated, 1.0);
+	assert_eq!(animated.pair.mode, Mode::Busy);
+}
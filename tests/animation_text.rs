@@ -0,0 +1,63 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use nablo_data::*;
+use nablo_shape::prelude::Animation;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use time::Duration;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Label {
+	text: String,
+}
+
+struct Animated {
+	label: Label,
+	map: HashMap<String, Animation>,
+	texts: HashMap<String, String>,
+}
+
+impl CanBeAnimated<'_, Label> for Animated {
+	fn get_animation_map(&mut self) -> &mut HashMap<String, Animation> {
+		&mut self.map
+	}
+
+	fn get_animate_target(&mut self) -> &mut Label {
+		&mut self.label
+	}
+
+	fn get_text_map(&self) -> Option<&HashMap<String, String>> {
+		Some(&self.texts)
+	}
+}
+
+fn animated(texts: HashMap<String, String>) -> Animated {
+	Animated {
+		label: Label { text: "abcd".to_string() },
+		map: AnimationMapBuilder::new("Label").field("text").animation(common::linear(0.0, 1.0, 4)).build(),
+		texts,
+	}
+}
+
+#[test]
+fn text_is_revealed_across_frames() {
+	let mut animated = animated(HashMap::from([(field_id(&["Label", "text"]), "abcdefgh".to_string())]));
+	let mut frames = vec![];
+	for second in 0..=4 {
+		animated.caculate(&Duration::seconds(second)).unwrap();
+		frames.push(animated.label.text.clone());
+	}
+	assert_eq!(frames, ["", "ab", "abcd", "abcdef", "abcdefgh"]);
+}
+
+#[test]
+fn text_without_entry_is_untouched() {
+	let mut animated = animated(HashMap::new());
+	for second in 0..=4 {
+		animated.caculate(&Duration::seconds(second)).unwrap();
+		assert_eq!(animated.label.text, "abcd");
+	}
+}
@@ -0,0 +1,130 @@
+use nablo_data::*;
+
+/// a small xorshift generator, so the generated trees are the same on every run
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	fn below(&mut self, max: u64) -> u64 {
+		self.next() % max
+	}
+}
+
+fn random_data(rng: &mut Rng, depth: usize) -> ParsedData {
+	let name = match rng.below(3) {
+		0 => String::new(),
+		1 => rng.below(10).to_string(),
+		_ => "field".to_string(),
+	};
+	let leaf = depth == 0;
+	let data = match rng.below(if leaf { 7 } else { 11 }) {
+		0 => DataEnum::String("héllo".chars().take(rng.below(6) as usize).collect()),
+		1 => DataEnum::Int((rng.next() as i128) << 64 | rng.next() as i128, i128::MIN..=i128::MAX),
+		2 => DataEnum::Int(rng.below(256) as i128, 0..=255),
+		3 => DataEnum::Float(f64::from_bits(rng.next())),
+		4 => DataEnum::Bool(rng.below(2) == 0),
+		5 => DataEnum::None,
+		6 => DataEnum::Data((0..rng.below(8)).map(|_| rng.next() as u8).collect()),
+		7 => DataEnum::Some(Box::new(random_data(rng, depth - 1))),
+		8 => DataEnum::Map(Box::new((random_data(rng, depth - 1), random_data(rng, depth - 1)))),
+		9 => DataEnum::Enum("Variant".to_string(), (0..rng.below(3)).map(|_| random_data(rng, depth - 1)).collect()),
+		_ => DataEnum::Node((0..rng.below(4)).map(|_| random_data(rng, depth - 1)).collect()),
+	};
+	ParsedData { data, name }
+}
+
+#[test]
+fn every_variant_roundtrips() {
+	#[derive(serde::Serialize)]
+	enum E {
+		A { x: i32 },
+		B(i32),
+	}
+	let leaves: ParsedData = vec![ParsedData::from(3u8), ParsedData::from(&b"abc"[..]), ParsedData::from(1.5f64), ParsedData::from("héllo"), ParsedData::from(true), ParsedData::default()].into();
+	let nested: ParsedData = vec![leaves.clone()].into();
+	let mixed = to_data(&(E::A { x: 3 }, E::B(1), std::collections::HashMap::from([(1, "a")]), Some(Some(2)))).unwrap();
+	for data in [leaves, nested, mixed] {
+		let bytes = to_bytes(&data).unwrap();
+		assert_eq!(from_bytes(&bytes).unwrap(), data);
+		for len in 0..bytes.len() {
+			assert!(from_bytes(&bytes[..len]).is_err());
+		}
+	}
+}
+
+#[test]
+fn random_trees_roundtrip() {
+	let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+	for _ in 0..500 {
+		let data = random_data(&mut rng, 4);
+		let bytes = to_bytes(&data).unwrap();
+		let decoded = from_bytes(&bytes).unwrap();
+		// compare the bytes, so NaN floats count as equal
+		assert_eq!(to_bytes(&decoded).unwrap(), bytes);
+	}
+}
+
+#[test]
+fn trailing_bytes_are_an_error() {
+	let mut bytes = to_bytes(&ParsedData::from(1)).unwrap();
+	bytes.push(0);
+	assert!(matches!(from_bytes(&bytes), Err(Error::Syntax)));
+}
+
+#[test]
+fn subtree_roundtrips() {
+	let child = to_data(&(1, "two")).unwrap();
+	assert_eq!(decode_subtree(&encode_subtree(&child).unwrap()).unwrap(), child);
+	assert!(decode_subtree(&DataEnum::Bool(true)).is_err());
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct Child {
+	values: Vec<i32>,
+	label: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+struct Parent {
+	name: String,
+	#[serde(with = "serde_bytes")]
+	child: Vec<u8>,
+}
+
+#[test]
+fn parent_carries_a_packed_child() {
+	let child = Child { values: vec![3, 1, 2], label: "packed".to_string() };
+	let mut data = to_data(&Parent { name: "parent".to_string(), child: vec![] }).unwrap();
+	data.set_path("child", encode_subtree(&to_data(&child).unwrap()).unwrap()).unwrap();
+	let parent: Parent = from_data(&mut data).unwrap();
+	assert_eq!(parent.name, "parent");
+	let mut unpacked = decode_subtree(&DataEnum::Data(parent.child)).unwrap();
+	assert_eq!(from_data::<Child>(&mut unpacked).unwrap(), child);
+}
+
+#[test]
+fn ints_outside_of_their_range_are_rejected() {
+	let int = |value, range| ParsedData { data: DataEnum::Int(value, range), name: String::new() };
+	assert!(from_bytes(&to_bytes(&int(3, 0..=255)).unwrap()).is_ok());
+	assert!(from_bytes(&to_bytes(&int(300, 0..=255)).unwrap()).is_err());
+	assert!(from_bytes(&to_bytes(&int(3, std::ops::RangeInclusive::new(255, 0))).unwrap()).is_err());
+}
+
+#[test]
+fn bounded_output_stops_at_the_limit() {
+	let items: Vec<String> = (0..10_000).map(|i| format!("item {}", i)).collect();
+	let data = to_data(&items).unwrap();
+	let full = to_bytes(&data).unwrap();
+	assert!(matches!(to_bytes_bounded(&data, 1024), Err(Error::SizeLimitExceeded(1024))));
+	assert_eq!(to_bytes_bounded(&data, full.len()).unwrap(), full);
+	assert!(matches!(to_bytes_bounded(&data, full.len() - 1), Err(Error::SizeLimitExceeded(_))));
+	assert!(to_bytes_bounded(&data, 0).is_err());
+	let blob = to_data(&serde_bytes::ByteBuf::from(vec![0u8; 1 << 20])).unwrap();
+	assert!(matches!(to_bytes_bounded(&blob, 100), Err(Error::SizeLimitExceeded(100))));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "cbor")]
+
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Kind {
+	Unit,
+	Newtype(i32),
+	Struct { q: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Inner {
+	z: f64,
+	y: Vec<u8>,
+	flag: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Outer {
+	x: i32,
+	inner: Inner,
+	kinds: Vec<Kind>,
+	names: BTreeMap<String, i32>,
+	ids: BTreeMap<u8, String>,
+	#[serde(with = "serde_bytes")]
+	blob: Vec<u8>,
+}
+
+fn roundtrip<T: Serialize + for<'a> Deserialize<'a>>(value: &T) -> T {
+	from_data(&mut from_cbor(&to_cbor(&to_data(value).unwrap()).unwrap()).unwrap()).unwrap()
+}
+
+#[test]
+fn nested_structs_and_maps_roundtrip() {
+	let value = Outer {
+		x: -5,
+		inner: Inner { z: 1.5, y: vec![1, 2], flag: None },
+		kinds: vec![Kind::Unit, Kind::Newtype(4), Kind::Struct { q: "w".to_string() }],
+		names: BTreeMap::from([("a".to_string(), 1), ("b".to_string(), -2)]),
+		ids: BTreeMap::from([(1, "one".to_string()), (200, "two hundred".to_string())]),
+		blob: vec![0, 255, 7],
+	};
+	assert_eq!(roundtrip(&value), value);
+	let value = Inner { flag: Some(true), ..value.inner };
+	assert_eq!(roundtrip(&value), value);
+}
+
+#[test]
+fn int_ranges_are_dropped() {
+	let data = from_cbor(&to_cbor(&to_data(&7u8).unwrap()).unwrap()).unwrap();
+	assert_eq!(data.data, DataEnum::Int(7, i128::MIN..=i128::MAX));
+	let mut data = to_data(&300u16).unwrap();
+	data = from_cbor(&to_cbor(&data).unwrap()).unwrap();
+	assert!(matches!(from_data::<u8>(&mut data), Err(Error::Overflow(300, "u8"))));
+}
+
+#[test]
+fn invalid_cbor_is_an_error() {
+	assert!(matches!(from_cbor(&[0xff, 0x00]), Err(Error::SerdeError(_))));
+}
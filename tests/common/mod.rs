@@ -0,0 +1,78 @@
+#![allow(dead_code)]
+
+use nablo_data::*;
+use nablo_shape::prelude::Animation;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use time::Duration;
+
+/// a animation going from `from` to `to` in `seconds`, starting at zero
+pub fn linear(from: f32, to: f32, seconds: i64) -> Animation {
+	delayed(0, from, to, seconds)
+}
+
+/// same as [`linear`], but starting at `start` seconds
+pub fn delayed(start: i64, from: f32, to: f32, seconds: i64) -> Animation {
+	Animation {
+		start_time: Duration::seconds(start),
+		start_value: from,
+		points: vec![(Duration::seconds(seconds), to)],
+	}
+}
+
+/// a target with every option of [`CanBeAnimated`], the options are empty unless set
+pub struct Animated<T> {
+	pub target: T,
+	pub map: HashMap<String, Animation>,
+	pub ranges: HashMap<String, RangeInclusive<f64>>,
+	pub texts: HashMap<String, String>,
+	pub variants: HashMap<String, Vec<(Duration, DataEnum)>>,
+	pub roundings: HashMap<String, IntRounding>,
+	pub modes: HashMap<String, PlayMode>,
+}
+
+impl<T> Animated<T> {
+	pub fn new(target: T, map: HashMap<String, Animation>) -> Self {
+		Self {
+			target,
+			map,
+			ranges: HashMap::new(),
+			texts: HashMap::new(),
+			variants: HashMap::new(),
+			roundings: HashMap::new(),
+			modes: HashMap::new(),
+		}
+	}
+}
+
+impl<'a, T: Serialize + Deserialize<'a>> CanBeAnimated<'a, T> for Animated<T> {
+	fn get_animation_map(&mut self) -> &mut HashMap<String, Animation> {
+		&mut self.map
+	}
+
+	fn get_animate_target(&mut self) -> &mut T {
+		&mut self.target
+	}
+
+	fn get_float_range_map(&self) -> Option<&HashMap<String, RangeInclusive<f64>>> {
+		Some(&self.ranges)
+	}
+
+	fn get_text_map(&self) -> Option<&HashMap<String, String>> {
+		Some(&self.texts)
+	}
+
+	fn get_variant_map(&self) -> Option<&HashMap<String, Vec<(Duration, DataEnum)>>> {
+		Some(&self.variants)
+	}
+
+	fn get_int_rounding_map(&self) -> Option<&HashMap<String, IntRounding>> {
+		Some(&self.roundings)
+	}
+
+	fn get_play_mode_map(&self) -> Option<&HashMap<String, PlayMode>> {
+		Some(&self.modes)
+	}
+}
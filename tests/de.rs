@@ -0,0 +1,641 @@
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Letter {
+	c: char,
+}
+
+fn letter(text: &str) -> Result<Letter, Error> {
+	let mut data = ParsedData::named("Letter", ParsedData::node(vec![ParsedData::named("c", text)]));
+	from_data(&mut data)
+}
+
+#[test]
+fn char_from_single_char() {
+	assert_eq!(letter("é").unwrap(), Letter { c: 'é' });
+	let mut data = to_data(&Letter { c: 'x' }).unwrap();
+	assert_eq!(from_data::<Letter>(&mut data).unwrap(), Letter { c: 'x' });
+}
+
+#[test]
+fn char_from_empty_string_is_an_error() {
+	assert!(matches!(letter(""), Err(Error::UnexpectedType(_, _))));
+}
+
+#[test]
+fn char_from_many_chars_is_an_error() {
+	assert!(matches!(letter("ab"), Err(Error::UnexpectedType(_, _))));
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Mixed {
+	Unit,
+	Newtype(i32),
+	Tuple(i32, bool),
+	Struct { q: String },
+}
+
+#[test]
+fn every_kind_of_variant_roundtrips() {
+	for value in [Mixed::Unit, Mixed::Newtype(3), Mixed::Tuple(-1, true), Mixed::Struct { q: "a".to_string() }] {
+		let mut data = to_data(&value).unwrap();
+		assert_eq!(from_data::<Mixed>(&mut data).unwrap(), value);
+	}
+	let values = vec![Mixed::Struct { q: "b".to_string() }, Mixed::Unit, Mixed::Tuple(2, false), Mixed::Unit];
+	let mut data = to_data(&values).unwrap();
+	assert_eq!(from_data::<Vec<Mixed>>(&mut data).unwrap(), values);
+}
+
+#[test]
+fn unit_variant_with_values_is_an_error() {
+	let mut data = to_data(&Mixed::Newtype(3)).unwrap();
+	if let DataEnum::Enum(variant, _) = &mut data.data {
+		*variant = "Unit".to_string();
+	}
+	assert!(from_data::<Mixed>(&mut data).is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Optional {
+	x: Option<Option<i32>>,
+	y: Option<Option<Option<()>>>,
+}
+
+#[test]
+fn nested_options_roundtrip() {
+	for value in [
+		Optional { x: None, y: None },
+		Optional { x: Some(None), y: Some(Some(None)) },
+		Optional { x: Some(Some(3)), y: Some(Some(Some(()))) },
+		Optional { x: Some(None), y: Some(None) },
+	] {
+		let mut data = to_data(&value).unwrap();
+		assert_eq!(from_data::<Optional>(&mut data).unwrap(), value);
+	}
+}
+
+#[test]
+fn options_in_sequences_roundtrip() {
+	let value = vec![None, Some(None), Some(Some(1))];
+	let mut data = to_data(&value).unwrap();
+	assert_eq!(from_data::<Vec<Option<Option<i32>>>>(&mut data).unwrap(), value);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Meters(f64);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Wrapper(Optional);
+
+#[test]
+fn newtype_structs_roundtrip() {
+	let mut data = to_data(&Meters(1.5)).unwrap();
+	assert_eq!(from_data::<Meters>(&mut data).unwrap(), Meters(1.5));
+	let value = Wrapper(Optional { x: Some(Some(2)), y: None });
+	let mut data = to_data(&value).unwrap();
+	assert_eq!(from_data::<Wrapper>(&mut data).unwrap(), value);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Typo {
+	a: i32,
+	b: bool,
+	typo: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Typo")]
+struct Fields {
+	a: i32,
+	b: bool,
+}
+
+#[test]
+fn strict_rejects_extra_fields() {
+	let mut data = to_data(&Typo { a: 1, b: true, typo: 1.0 }).unwrap();
+	assert!(matches!(from_data_strict::<Fields>(&mut data), Err(Error::UnknownField(field)) if field == "typo"));
+}
+
+#[test]
+fn lenient_ignores_extra_fields() {
+	let mut data = to_data(&Typo { a: 1, b: true, typo: 1.0 }).unwrap();
+	assert_eq!(from_data::<Fields>(&mut data).unwrap(), Fields { a: 1, b: true });
+}
+
+#[test]
+fn strict_accepts_exact_fields() {
+	let mut data = to_data(&Fields { a: 1, b: true }).unwrap();
+	assert_eq!(from_data_strict::<Fields>(&mut data).unwrap(), Fields { a: 1, b: true });
+}
+
+#[test]
+fn tuple_keyed_map_roundtrips() {
+	let value: std::collections::HashMap<(i32, i32), String> = [((1, 1), "a".to_string()), ((2, -2), "b".to_string())].into();
+	let mut data = to_data(&value).unwrap();
+	assert_eq!(from_data::<std::collections::HashMap<(i32, i32), String>>(&mut data).unwrap(), value);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+	x: i32,
+	name: String,
+}
+
+#[test]
+fn struct_keyed_map_roundtrips() {
+	let value = std::collections::BTreeMap::from([(Key { x: 1, name: "a".to_string() }, 1.5), (Key { x: 2, name: "b".to_string() }, 2.5)]);
+	let mut data = to_data(&value).unwrap();
+	assert_eq!(from_data::<std::collections::BTreeMap<Key, f64>>(&mut data).unwrap(), value);
+}
+
+#[test]
+fn unexpected_type_names_both_types() {
+	let mut data = ParsedData::named("Letter", ParsedData::node(vec![ParsedData::named("c", 1.5)]));
+	let error = from_data::<Letter>(&mut data).unwrap_err().to_string();
+	assert!(error.contains("expect: char"), "{}", error);
+	assert!(error.contains("found: float"), "{}", error);
+}
+
+#[test]
+fn type_names() {
+	assert_eq!(ParsedData::from(1.5).data.type_name(), "float");
+	assert_eq!(ParsedData::from("a").data.type_name(), "string");
+	assert_eq!(ParsedData::node(vec![]).data.type_name(), "node");
+	assert_eq!(DataEnum::None.type_name(), "none");
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Mixed")]
+enum Old {
+	Unit,
+	Removed(i32),
+}
+
+#[test]
+fn unknown_variant_lists_the_known_ones() {
+	let mut data = to_data(&Old::Removed(1)).unwrap();
+	match from_data::<Mixed>(&mut data) {
+		Err(Error::UnknownVariant(variant, expected)) => {
+			assert_eq!(variant, "Removed");
+			assert_eq!(expected, ["Unit", "Newtype", "Tuple", "Struct"]);
+		},
+		other => panic!("{:?}", other),
+	}
+	let mut data = to_data(&Old::Unit).unwrap();
+	assert_eq!(from_data::<Mixed>(&mut data).unwrap(), Mixed::Unit);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Particle {
+	position: (f32, f32),
+	name: String,
+	bytes: Vec<u8>,
+	kind: Mixed,
+	tags: std::collections::BTreeMap<String, Option<i64>>,
+}
+
+#[test]
+fn large_vector_of_structs_roundtrips() {
+	let particles: Vec<Particle> = (0..2000).map(|index| Particle {
+		position: (index as f32, -(index as f32)),
+		name: format!("particle {}", index),
+		bytes: vec![index as u8; index % 7],
+		kind: match index % 4 {
+			0 => Mixed::Unit,
+			1 => Mixed::Newtype(index as i32),
+			2 => Mixed::Tuple(index as i32, index % 3 == 0),
+			_ => Mixed::Struct { q: index.to_string() },
+		},
+		tags: [(index.to_string(), Some(index as i64)), ("none".to_string(), None)].into(),
+	}).collect();
+	let mut data = to_data(&particles).unwrap();
+	assert_eq!(from_data_ref::<Vec<Particle>>(&data).unwrap(), particles);
+	assert_eq!(from_data::<Vec<Particle>>(&mut data).unwrap(), particles);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename = "Typo")]
+struct OnlyTypo {
+	typo: f32,
+}
+
+#[test]
+fn from_data_ref_reads_one_tree_into_many_types() {
+	let data = to_data(&Typo { a: 1, b: true, typo: 2.0 }).unwrap();
+	let before = data.clone();
+	assert_eq!(from_data_ref::<Fields>(&data).unwrap(), Fields { a: 1, b: true });
+	assert_eq!(from_data_ref::<OnlyTypo>(&data).unwrap(), OnlyTypo { typo: 2.0 });
+	assert_eq!(data, before);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Texture {
+	#[serde(with = "serde_bytes")]
+	pixels: Vec<u8>,
+}
+
+#[test]
+fn byte_buffers_are_moved_out() {
+	let texture = Texture { pixels: (0..4 << 20).map(|index| index as u8).collect() };
+	let mut data = to_data(&texture).unwrap();
+	let pointer = match &data.get("pixels").unwrap().data {
+		DataEnum::Data(inner) => inner.as_ptr(),
+		other => panic!("{:?}", other),
+	};
+	let back = from_data::<Texture>(&mut data).unwrap();
+	assert_eq!(back.pixels.as_ptr(), pointer);
+	assert_eq!(back, texture);
+}
+
+#[test]
+fn borrowed_bytes_are_still_visited() {
+	struct Length(usize);
+
+	impl<'de> Deserialize<'de> for Length {
+		fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+			struct LengthVisitor;
+
+			impl serde::de::Visitor<'_> for LengthVisitor {
+				type Value = Length;
+
+				fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+					formatter.write_str("bytes")
+				}
+
+				fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Length, E> {
+					Ok(Length(bytes.len()))
+				}
+			}
+
+			deserializer.deserialize_bytes(LengthVisitor)
+		}
+	}
+
+	let mut data = ParsedData::from(&b"abc"[..]);
+	assert_eq!(from_data::<Length>(&mut data).unwrap().0, 3);
+}
+
+/// what `deserialize_any` visited, a seq with its length or a map with its keys
+#[derive(Debug, PartialEq)]
+enum Visited {
+	Seq(usize),
+	Map(Vec<String>),
+}
+
+impl<'de> Deserialize<'de> for Visited {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct VisitedVisitor;
+
+		impl<'de> serde::de::Visitor<'de> for VisitedVisitor {
+			type Value = Visited;
+
+			fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+				formatter.write_str("a seq or a map")
+			}
+
+			fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Visited, A::Error> {
+				let mut len = 0;
+				while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+					len += 1;
+				}
+				Ok(Visited::Seq(len))
+			}
+
+			fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Visited, A::Error> {
+				let mut keys = vec![];
+				while let Some((key, _)) = map.next_entry::<String, serde::de::IgnoredAny>()? {
+					keys.push(key);
+				}
+				Ok(Visited::Map(keys))
+			}
+		}
+
+		deserializer.deserialize_any(VisitedVisitor)
+	}
+}
+
+fn visited(children: Vec<ParsedData>) -> Visited {
+	from_data(&mut ParsedData::node(children)).unwrap()
+}
+
+#[test]
+fn any_with_unnamed_children_is_a_seq() {
+	assert_eq!(visited(vec![ParsedData::from(1), ParsedData::from(2)]), Visited::Seq(2));
+	assert_eq!(visited(vec![ParsedData::named("0", 1), ParsedData::named("1", 2)]), Visited::Seq(2));
+}
+
+#[test]
+fn any_with_named_children_is_a_map() {
+	assert_eq!(visited(vec![ParsedData::named("a", 1), ParsedData::named("b", 2)]), Visited::Map(vec!["a".to_string(), "b".to_string()]));
+	let mut data = to_data(&std::collections::BTreeMap::from([("x", 1), ("y", 2)])).unwrap();
+	assert_eq!(from_data::<Visited>(&mut data).unwrap(), Visited::Map(vec!["x".to_string(), "y".to_string()]));
+}
+
+#[test]
+fn any_with_mixed_children_is_a_map_keyed_by_name_or_index() {
+	assert_eq!(visited(vec![ParsedData::named("a", 1), ParsedData::from(2), ParsedData::named("c", 3)]), Visited::Map(vec!["a".to_string(), "1".to_string(), "c".to_string()]));
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Small {
+	byte: u8,
+	short: i16,
+}
+
+fn small(byte: i128, short: i128) -> Result<Small, Error> {
+	from_data(&mut ParsedData::named("Small", ParsedData::node(vec![ParsedData::named("byte", byte), ParsedData::named("short", short)])))
+}
+
+#[test]
+fn out_of_range_ints_are_an_overflow() {
+	let error = small(300, 0).unwrap_err();
+	assert!(matches!(error, Error::Overflow(300, "u8")), "{:?}", error);
+	let error = small(-1, 0).unwrap_err();
+	assert!(matches!(error, Error::Overflow(-1, "u8")), "{:?}", error);
+	let error = small(0, 40000).unwrap_err();
+	assert!(matches!(error, Error::Overflow(40000, "i16")), "{:?}", error);
+	let error = small(0, -40000).unwrap_err();
+	assert!(matches!(error, Error::Overflow(-40000, "i16")), "{:?}", error);
+}
+
+#[test]
+fn ints_at_the_edge_of_the_range_fit() {
+	let value = small(255, -32768).unwrap();
+	assert_eq!((value.byte, value.short), (255, i16::MIN));
+}
+
+#[test]
+fn arrays_and_tuples_keep_their_order() {
+	let array = [1, 2, 3, 4];
+	let mut data = to_data(&array).unwrap();
+	assert_eq!(from_data::<[i32; 4]>(&mut data).unwrap(), array);
+	let tuple = (1u8, "two".to_string(), 3.0f32);
+	let mut data = to_data(&tuple).unwrap();
+	assert_eq!(from_data::<(u8, String, f32)>(&mut data).unwrap(), tuple);
+}
+
+#[test]
+fn vec_keeps_its_order() {
+	let value: Vec<String> = ["first", "second", "third", "fourth"].into_iter().map(String::from).collect();
+	let mut data = to_data(&value).unwrap();
+	assert_eq!(from_data::<Vec<String>>(&mut data).unwrap(), value);
+	let mut data = ParsedData::node(vec![ParsedData::named("0", "z"), ParsedData::named("1", "a")]);
+	assert_eq!(from_data::<Vec<String>>(&mut data).unwrap(), ["z", "a"]);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Flattened {
+	x: f32,
+	name: String,
+	values: Vec<u8>,
+	one: Vec<i32>,
+	pair: (u8, bool),
+	flags: std::collections::BTreeMap<u8, bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Single {
+	only: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct WithFlatten {
+	id: u32,
+	#[serde(flatten)]
+	inner: Flattened,
+	on: bool,
+	maybe: Option<i8>,
+	single: Single,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct WithRest {
+	id: u32,
+	#[serde(flatten)]
+	rest: std::collections::BTreeMap<String, i32>,
+}
+
+#[test]
+fn flattened_struct_roundtrips() {
+	let value = WithFlatten {
+		id: 3,
+		inner: Flattened {
+			x: 1.5,
+			name: "n".to_string(),
+			values: vec![1, 2],
+			one: vec![7],
+			pair: (1, true),
+			flags: [(1, true), (2, false)].into(),
+		},
+		on: true,
+		maybe: Some(-1),
+		single: Single { only: 4 },
+	};
+	let mut data = to_data(&value).unwrap();
+	assert_eq!(from_data::<WithFlatten>(&mut data).unwrap(), value);
+}
+
+#[test]
+fn flattened_map_roundtrips() {
+	let value = WithRest { id: 1, rest: [("a".to_string(), 1), ("b".to_string(), 2)].into() };
+	let mut data = to_data(&value).unwrap();
+	assert_eq!(from_data::<WithRest>(&mut data).unwrap(), value);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+struct Checked {
+	x: f32,
+	n: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+struct Validated {
+	a: u8,
+	name: String,
+	inner: Checked,
+	list: Vec<u8>,
+}
+
+fn validated() -> Validated {
+	Validated { a: 1, name: "n".to_string(), inner: Checked { x: 1.0, n: 2 }, list: vec![1, 2, 3] }
+}
+
+#[test]
+fn collect_returns_valid_values() {
+	assert_eq!(from_data_collect::<Validated>(&to_data(&validated()).unwrap()).unwrap(), validated());
+}
+
+#[test]
+fn collect_reports_every_bad_field() {
+	let mut data = to_data(&validated()).unwrap();
+	data.set_path("a", DataEnum::String("bad".to_string())).unwrap();
+	data.set_path("inner.n", DataEnum::Int(1000, 0..=2000)).unwrap();
+	data.set_path("list.1", DataEnum::Bool(true)).unwrap();
+	let errors = from_data_collect::<Validated>(&data).unwrap_err();
+	let ids: Vec<&str> = errors.iter().map(|(id, _)| id.as_str()).collect();
+	assert_eq!(ids, [field_id(&["Validated", "a"]), field_id(&["Validated", "inner", "n"]), field_id(&["Validated", "list", "1"])]);
+	assert!(matches!(errors[0].1, Error::UnexpectedType(..)));
+	assert!(matches!(errors[1].1, Error::Overflow(1000, "u8")));
+	// from_data still stops at the first one
+	assert!(matches!(from_data::<Validated>(&mut data), Err(Error::UnexpectedType(..))));
+}
+
+fn owned_roundtrip<T: Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+	from_data_owned(&mut to_data(value).unwrap()).unwrap()
+}
+
+#[test]
+fn from_data_owned_in_generic_code() {
+	let value = vec!["a".to_string(), "bc".to_string(), String::new()];
+	assert_eq!(owned_roundtrip(&value), value);
+	let output: Vec<String> = from_data_owned(&mut to_data(&value).unwrap()).unwrap();
+	assert_eq!(output, value);
+}
+
+#[test]
+fn large_u64_does_not_wrap_into_i64() {
+	let value = i64::MAX as u64 + 1;
+	let data = to_data(&value).unwrap();
+	assert_eq!(from_data::<u64>(&mut data.clone()).unwrap(), value);
+	assert!(matches!(from_data::<i64>(&mut data.clone()), Err(Error::Overflow(t, "i64")) if t == value as i128));
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+struct Id(u32);
+
+#[test]
+fn maps_with_non_string_keys_roundtrip() {
+	use std::collections::HashMap;
+	let ints: HashMap<i32, String> = [(-3, "a".to_string()), (7, "b".to_string())].into();
+	assert_eq!(from_data::<HashMap<i32, String>>(&mut to_data(&ints).unwrap()).unwrap(), ints);
+	assert_eq!(from_data_strict::<HashMap<i32, String>>(&mut to_data(&ints).unwrap()).unwrap(), ints);
+	let flags: HashMap<bool, u8> = [(true, 1), (false, 0)].into();
+	assert_eq!(from_data::<HashMap<bool, u8>>(&mut to_data(&flags).unwrap()).unwrap(), flags);
+	let tuples: HashMap<(u8, char), f32> = [((1, 'x'), 0.5)].into();
+	assert_eq!(from_data::<HashMap<(u8, char), f32>>(&mut to_data(&tuples).unwrap()).unwrap(), tuples);
+}
+
+#[test]
+fn named_children_parse_into_the_key_type() {
+	use std::collections::HashMap;
+	let named = || ParsedData::node(vec![ParsedData::named("5", 1u8), ParsedData::named("-6", 2u8)]);
+	assert_eq!(from_data::<HashMap<i32, u8>>(&mut named()).unwrap(), [(5, 1), (-6, 2)].into());
+	assert_eq!(from_data::<HashMap<String, u8>>(&mut named()).unwrap(), [("5".to_string(), 1), ("-6".to_string(), 2)].into());
+	assert!(matches!(from_data::<HashMap<u8, u8>>(&mut named()), Err(Error::UnexpectedType(t, "string")) if t == "u8"));
+	let mut flags = ParsedData::node(vec![ParsedData::named("true", 1u8), ParsedData::named("false", 0u8)]);
+	assert_eq!(from_data::<HashMap<bool, u8>>(&mut flags).unwrap(), [(true, 1), (false, 0)].into());
+	let mut ids = ParsedData::node(vec![ParsedData::named("3", 1u8)]);
+	assert_eq!(from_data::<HashMap<Id, u8>>(&mut ids).unwrap(), [(Id(3), 1)].into());
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+enum Slot {
+	A,
+	B,
+}
+
+#[test]
+fn wide_int_keys_roundtrip() {
+	use std::collections::HashMap;
+	let value: HashMap<u128, String> = [(0, "z".to_string()), (u64::MAX as u128 + 1, "a".to_string()), (i128::MAX as u128, "m".to_string()), (u128::MAX, "x".to_string())].into();
+	let data = to_data(&value).unwrap();
+	assert!(data.get(&u128::MAX.to_string()).is_some());
+	assert_eq!(from_data::<HashMap<u128, String>>(&mut data.clone()).unwrap(), value);
+	assert_eq!(from_data::<HashMap<u128, String>>(&mut from_bytes(&to_bytes(&data).unwrap()).unwrap()).unwrap(), value);
+	let value: HashMap<i128, String> = [(i128::MIN, "min".to_string()), (-1, "n".to_string())].into();
+	assert_eq!(from_data::<HashMap<i128, String>>(&mut to_data(&value).unwrap()).unwrap(), value);
+	let mut named = ParsedData::node(vec![ParsedData::named(u128::MAX.to_string(), "x")]);
+	assert_eq!(from_data::<HashMap<u128, String>>(&mut named).unwrap(), [(u128::MAX, "x".to_string())].into());
+}
+
+#[test]
+fn u128_above_i128_max_only_works_as_a_key() {
+	assert!(to_data(&u128::MAX).is_err());
+	assert!(to_data(&vec![(u128::MAX, 1u8)]).is_err());
+}
+
+#[test]
+fn enum_keys_roundtrip() {
+	use std::collections::HashMap;
+	let value: HashMap<Slot, u8> = [(Slot::A, 1), (Slot::B, 2)].into();
+	assert_eq!(from_data::<HashMap<Slot, u8>>(&mut to_data(&value).unwrap()).unwrap(), value);
+	let mut named = ParsedData::node(vec![ParsedData::named("B", 2u8)]);
+	assert_eq!(from_data::<HashMap<Slot, u8>>(&mut named).unwrap(), [(Slot::B, 2)].into());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct WithSkip {
+	a: u8,
+	#[serde(skip)]
+	cache: Vec<u8>,
+	b: String,
+	c: f32,
+}
+
+#[test]
+fn skipped_field_in_the_middle_gets_its_default() {
+	let value = WithSkip { a: 1, cache: vec![9], b: "b".to_string(), c: 2.5 };
+	let mut data = to_data(&value).unwrap();
+	assert!(data.get("cache").is_none());
+	assert_eq!(from_data::<WithSkip>(&mut data).unwrap(), WithSkip { cache: vec!(), ..value });
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Ordered {
+	first: u8,
+	second: String,
+	third: bool,
+}
+
+#[test]
+fn struct_fields_are_matched_by_name_not_position() {
+	let mut data = ParsedData::named("Ordered", ParsedData::node(vec![
+		ParsedData::named("third", true),
+		ParsedData::named("second", "s"),
+		ParsedData::named("first", 7u8),
+	]));
+	assert_eq!(from_data::<Ordered>(&mut data).unwrap(), Ordered { first: 7, second: "s".to_string(), third: true });
+}
+
+#[test]
+fn unnamed_struct_fields_still_fall_back_to_their_position() {
+	let mut data = ParsedData::named("Ordered", ParsedData::node(vec![ParsedData::from(7u8), "s".into(), true.into()]));
+	assert_eq!(from_data::<Ordered>(&mut data).unwrap(), Ordered { first: 7, second: "s".to_string(), third: true });
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Triple(i32, String, bool);
+
+#[test]
+fn heterogeneous_tuple_fields_land_in_their_slot() {
+	let mut data = to_data(&(1, "a", true)).unwrap();
+	let (number, text, flag) = from_data::<(i32, String, bool)>(&mut data).unwrap();
+	assert_eq!(number, 1);
+	assert_eq!(text, "a");
+	assert!(flag);
+	let value = Triple(-2, "b".to_string(), false);
+	assert_eq!(from_data::<Triple>(&mut to_data(&value).unwrap()).unwrap(), value);
+}
+
+#[test]
+fn tuples_with_extra_elements_are_an_error() {
+	let mut data = to_data(&(1u8, 2u8, 3u8)).unwrap();
+	assert!(from_data::<(u8, u8)>(&mut data).is_err());
+	let mut data = to_data(&(1u8, 2u8, 3u8, 4u8)).unwrap();
+	assert!(from_data::<Triple>(&mut data).is_err());
+}
+
+#[test]
+fn some_unit_stays_apart_from_none() {
+	let some = to_data(&Some(())).unwrap();
+	let none = to_data(&None::<()>).unwrap();
+	assert!(matches!(&some.data, DataEnum::Some(inner) if inner.data == DataEnum::None));
+	assert_eq!(none.data, DataEnum::None);
+	assert_eq!(to_data(&()).unwrap().data, DataEnum::None);
+	assert_eq!(from_data::<Option<()>>(&mut some.clone()).unwrap(), Some(()));
+	assert_eq!(from_data::<Option<()>>(&mut none.clone()).unwrap(), None);
+	assert_eq!(from_data::<Vec<Option<()>>>(&mut to_data(&vec![Some(()), None]).unwrap()).unwrap(), vec![Some(()), None]);
+}
@@ -0,0 +1,348 @@
+#![cfg(feature = "std")]
+
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Config {
+	volume: f64,
+	level: u8,
+	muted: bool,
+	label: String,
+}
+
+fn config() -> Config {
+	Config { volume: 1.0, level: 1, muted: false, label: "a".to_string() }
+}
+
+#[test]
+fn bool_and_string_changes_are_recorded() {
+	let left = Config { volume: 3.0, level: 2, muted: true, label: "b".to_string() };
+	let delta = caculate_delta_value(&left, &config()).unwrap();
+	assert_eq!(delta, HashMap::from([
+		(field_id(&["Config", "volume"]), DeltaValue::Number(2.0)),
+		(field_id(&["Config", "level"]), DeltaValue::Number(1.0)),
+		(field_id(&["Config", "muted"]), DeltaValue::Bool(true)),
+		(field_id(&["Config", "label"]), DeltaValue::String("b".to_string())),
+	]));
+	let mut right = config();
+	apply_delta_value(&mut right, &delta).unwrap();
+	assert_eq!(right, left);
+}
+
+#[test]
+fn numeric_delta_skips_bool_and_string() {
+	let left = Config { volume: 3.0, muted: true, label: "b".to_string(), ..config() };
+	let delta = caculate_delta(&left, &config()).unwrap();
+	assert_eq!(delta, HashMap::from([(field_id(&["Config", "volume"]), 2.0)]));
+	let mut right = config();
+	apply_delta(&mut right, &delta).unwrap();
+	assert_eq!(right, Config { volume: 3.0, ..config() });
+}
+
+#[test]
+fn equal_values_have_no_delta() {
+	assert!(caculate_delta_value(&config(), &config()).unwrap().is_empty());
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Floats {
+	a: f64,
+	b: f64,
+}
+
+#[test]
+fn non_finite_deltas_are_skipped_by_default() {
+	let left = Floats { a: f64::NAN, b: 2.0 };
+	let right = Floats { a: 1.0, b: 1.0 };
+	assert_eq!(caculate_delta_value(&left, &right).unwrap(), HashMap::from([(field_id(&["Floats", "b"]), DeltaValue::Number(1.0))]));
+	let infinite = Floats { a: f64::INFINITY, b: 1.0 };
+	assert!(caculate_delta(&infinite, &right).unwrap().is_empty());
+}
+
+#[test]
+fn non_finite_deltas_can_be_errors() {
+	let left = Floats { a: f64::NAN, b: 2.0 };
+	let right = Floats { a: 1.0, b: 1.0 };
+	assert!(matches!(caculate_delta_value_with(&left, &right, NonFinitePolicy::Error), Err(Error::NonFinite(id)) if id == field_id(&["Floats", "a"])));
+}
+
+#[test]
+fn non_finite_deltas_are_not_applied() {
+	let right = Floats { a: 1.0, b: 1.0 };
+	let delta = HashMap::from([(field_id(&["Floats", "a"]), DeltaValue::Number(f64::INFINITY)), (field_id(&["Floats", "b"]), DeltaValue::Number(f64::NAN))]);
+	let mut value = right.clone();
+	apply_delta_value(&mut value, &delta).unwrap();
+	assert_eq!(value, right);
+	assert!(matches!(apply_delta_value_with(&mut value, &delta, NonFinitePolicy::Error), Err(Error::NonFinite(_))));
+	let mut value = right.clone();
+	apply_delta(&mut value, &HashMap::from([(field_id(&["Floats", "a"]), f64::NAN)])).unwrap();
+	assert_eq!(value, right);
+}
+
+#[test]
+fn scaled_delta_applies_a_fraction() {
+	let left = Config { volume: 3.0, level: 9, ..config() };
+	let delta = caculate_delta(&left, &config()).unwrap();
+	let scaled = |factor| {
+		let mut value = config();
+		apply_delta_scaled(&mut value, &delta, factor).unwrap();
+		value
+	};
+	assert_eq!(scaled(0.0), config());
+	assert_eq!(scaled(0.5), Config { volume: 2.0, level: 5, ..config() });
+	assert_eq!(scaled(1.0), left);
+	let mut value = config();
+	apply_delta(&mut value, &delta).unwrap();
+	assert_eq!(scaled(1.0), value);
+}
+
+#[test]
+fn scaled_delta_clamps_ints_into_their_range() {
+	let delta = caculate_delta(&Config { level: 9, ..config() }, &config()).unwrap();
+	let mut value = config();
+	apply_delta_scaled(&mut value, &delta, 100.0).unwrap();
+	assert_eq!(value.level, u8::MAX);
+	let mut value = config();
+	apply_delta_scaled(&mut value, &delta, -100.0).unwrap();
+	assert_eq!(value.level, 0);
+}
+
+#[test]
+fn delta_into_replaces_stale_entries() {
+	let mut map = HashMap::from([("stale".to_string(), 1.0)]);
+	caculate_delta_into(&Config { volume: 2.0, ..config() }, &config(), &mut map).unwrap();
+	assert_eq!(map, HashMap::from([(field_id(&["Config", "volume"]), 1.0)]));
+	caculate_delta_into(&Config { level: 3, ..config() }, &config(), &mut map).unwrap();
+	assert_eq!(map, HashMap::from([(field_id(&["Config", "level"]), 2.0)]));
+	assert_eq!(map, caculate_delta(&Config { level: 3, ..config() }, &config()).unwrap());
+}
+
+#[test]
+fn percent_delta_is_relative_to_right() {
+	let left = Config { volume: 1.5, level: 2, ..config() };
+	assert_eq!(caculate_delta_percent(&left, &config()).unwrap(), HashMap::from([(field_id(&["Config", "volume"]), 0.5), (field_id(&["Config", "level"]), 1.0)]));
+	let left = Config { volume: 0.5, ..config() };
+	assert_eq!(caculate_delta_percent(&left, &config()).unwrap(), HashMap::from([(field_id(&["Config", "volume"]), -0.5)]));
+}
+
+#[test]
+fn percent_delta_skips_zero_baselines() {
+	let right = Config { volume: 0.0, level: 0, ..config() };
+	let left = Config { volume: 1.0, level: 1, ..config() };
+	assert!(caculate_delta_percent(&left, &right).unwrap().is_empty());
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Inventory {
+	items: std::collections::BTreeMap<String, i32>,
+	note: Option<String>,
+	weight: Option<f64>,
+}
+
+#[test]
+fn maps_with_disjoint_keys() {
+	let left = Inventory { items: [("a".to_string(), 1), ("b".to_string(), 2)].into(), note: None, weight: None };
+	let right = Inventory { items: [("b".to_string(), 1), ("c".to_string(), 3)].into(), note: None, weight: None };
+	let delta = caculate_delta_value(&left, &right).unwrap();
+	assert_eq!(delta, HashMap::from([
+		(field_id(&["Inventory", "items", "a", ""]), DeltaValue::Added(DataEnum::Int(1, i32::MIN as i128..=i32::MAX as i128))),
+		(field_id(&["Inventory", "items", "b", ""]), DeltaValue::Number(1.0)),
+		(field_id(&["Inventory", "items", "c", ""]), DeltaValue::Removed),
+	]));
+	let mut value = right.clone();
+	apply_delta_value(&mut value, &delta).unwrap();
+	// `a` is missing in `right`, so it can not be added back
+	assert_eq!(value.items, [("b".to_string(), 2)].into());
+}
+
+#[test]
+fn options_going_from_some_to_none() {
+	let left = Inventory { items: Default::default(), note: Some("x".to_string()), weight: None };
+	let right = Inventory { items: Default::default(), note: None, weight: Some(2.0) };
+	let delta = caculate_delta_value(&left, &right).unwrap();
+	assert_eq!(delta, HashMap::from([
+		(field_id(&["Inventory", "note"]), DeltaValue::Added(DataEnum::String("x".to_string()))),
+		(field_id(&["Inventory", "weight"]), DeltaValue::Removed),
+	]));
+	let mut value = right.clone();
+	apply_delta_value(&mut value, &delta).unwrap();
+	assert_eq!(value, left);
+}
+
+#[test]
+fn clamped_delta_keeps_fields_in_their_range() {
+	let delta = HashMap::from([(field_id(&["Config", "volume"]), 0.7), (field_id(&["Config", "level"]), 9.0)]);
+	let clamps = HashMap::from([(field_id(&["Config", "volume"]), 0.0..=1.0), (field_id(&["Config", "level"]), 0.0..=5.0)]);
+	let mut value = Config { volume: 0.5, ..config() };
+	apply_delta_clamped(&mut value, &delta, &clamps).unwrap();
+	assert_eq!((value.volume, value.level), (1.0, 5));
+	let delta = HashMap::from([(field_id(&["Config", "volume"]), -2.0)]);
+	apply_delta_clamped(&mut value, &delta, &clamps).unwrap();
+	assert_eq!(value.volume, 0.0);
+}
+
+#[test]
+fn clamped_delta_skips_int_ranges_without_ints() {
+	let delta = HashMap::from([(field_id(&["Config", "level"]), 9.0)]);
+	let mut value = config();
+	apply_delta_clamped(&mut value, &delta, &HashMap::from([(field_id(&["Config", "level"]), 0.2..=0.8)])).unwrap();
+	assert_eq!(value.level, 10);
+	apply_delta_clamped(&mut value, &delta, &HashMap::from([(field_id(&["Config", "level"]), 5.5..=2.5)])).unwrap();
+	assert_eq!(value.level, 5);
+}
+
+#[test]
+fn clamped_delta_leaves_other_fields_unclamped() {
+	let delta = HashMap::from([(field_id(&["Config", "volume"]), 5.0)]);
+	let mut value = config();
+	apply_delta_clamped(&mut value, &delta, &HashMap::new()).unwrap();
+	assert_eq!(value.volume, 6.0);
+}
+
+#[test]
+fn sorted_deltas_orders_by_magnitude_then_id() {
+	let map: HashMap<String, f64> = [("a", 1.0), ("b", -5.0), ("c", 3.0), ("d", -1.0), ("e", 0.0), ("f", 5.0)]
+		.into_iter()
+		.map(|(id, t)| (id.to_string(), t))
+		.collect();
+	let sorted: Vec<(&str, f64)> = sorted_deltas(&map).into_iter().map(|(id, t)| (id.as_str(), t)).collect();
+	assert_eq!(sorted, [("b", -5.0), ("f", 5.0), ("c", 3.0), ("a", 1.0), ("d", -1.0), ("e", 0.0)]);
+}
+
+fn blend(a: &Config, b: &Config, t: f64) -> Config {
+	let mut data = interpolate(&to_data(a).unwrap(), &to_data(b).unwrap(), t).unwrap();
+	from_data(&mut data).unwrap()
+}
+
+#[test]
+fn interpolate_blends_numeric_fields() {
+	let a = config();
+	let b = Config { volume: 3.0, level: 5, muted: true, label: "b".to_string() };
+	assert_eq!(blend(&a, &b, 0.0), Config { volume: 1.0, level: 1, ..b.clone() });
+	assert_eq!(blend(&a, &b, 0.5), Config { volume: 2.0, level: 3, ..b.clone() });
+	assert_eq!(blend(&a, &b, 1.0), b);
+}
+
+#[test]
+fn interpolate_rejects_different_shapes() {
+	let a = to_data(&vec![1.0f32, 2.0]).unwrap();
+	let b = to_data(&vec![1.0f32]).unwrap();
+	assert!(matches!(interpolate(&a, &b, 0.5), Err(Error::At(_, inner)) if matches!(*inner, Error::UnexpectedType(..))));
+	let a = to_data(&config()).unwrap();
+	let b = to_data(&Floats { a: 0.0, b: 0.0 }).unwrap();
+	assert!(interpolate(&a, &b, 0.5).is_err());
+}
+
+#[test]
+fn interpolate_matches_map_entries_by_key() {
+	let a = ParsedData::node(vec![ParsedData::map("x", 1.0), ParsedData::map("y", 10.0)]);
+	let b = ParsedData::node(vec![ParsedData::map("y", 20.0), ParsedData::map("x", 3.0)]);
+	let mut blended = interpolate(&a, &b, 0.5).unwrap();
+	assert_eq!(from_data::<BTreeMap<String, f64>>(&mut blended).unwrap(), BTreeMap::from([("x".to_string(), 2.0), ("y".to_string(), 15.0)]));
+	let c = ParsedData::node(vec![ParsedData::map("x", 3.0), ParsedData::map("z", 20.0)]);
+	assert!(matches!(interpolate(&a, &c, 0.5), Err(Error::At(_, inner)) if matches!(*inner, Error::UnknownField(ref key) if key == "z")));
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Stats {
+	count: u32,
+	account: u32,
+	timing: Floats,
+}
+
+#[test]
+fn filtered_delta_skips_ignored_fields_and_subtrees() {
+	let left = Stats { count: 5, account: 7, timing: Floats { a: 2.0, b: 3.0 } };
+	let right = Stats { count: 1, account: 1, timing: Floats { a: 0.0, b: 0.0 } };
+	let delta = caculate_delta_filtered(&left, &right, &["----count"]).unwrap();
+	assert_eq!(delta.len(), 3);
+	assert_eq!(delta.get(&field_id(&["Stats", "account"])), Some(&6.0));
+	let delta = caculate_delta_filtered(&left, &right, &[&field_id(&["Stats", "timing"]), "----timing----b"]).unwrap();
+	assert_eq!(delta, HashMap::from([(field_id(&["Stats", "count"]), 4.0), (field_id(&["Stats", "account"]), 6.0)]));
+	let delta = caculate_delta_filtered(&left, &right, &["----b"]).unwrap();
+	assert_eq!(delta.len(), 3);
+	assert!(!delta.contains_key(&field_id(&["Stats", "timing", "b"])));
+	assert_eq!(caculate_delta_filtered(&left, &right, &[]).unwrap(), caculate_delta(&left, &right).unwrap());
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Single {
+	x: f32,
+}
+
+#[test]
+fn repeated_f32_delta_drifts_like_f32_arithmetic() {
+	let mut value = Single { x: 1.0 };
+	let mut expected = 1.0f32;
+	let delta = HashMap::from([(field_id(&["Single", "x"]), 0.001)]);
+	for _ in 0..10_000 {
+		apply_delta(&mut value, &delta).unwrap();
+		expected += 0.001;
+	}
+	assert!((value.x as f64 - 11.0).abs() < 1e-2, "{}", value.x);
+	assert!((value.x - 11.0).abs() <= (expected - 11.0).abs() + 1e-3, "{} {}", value.x, expected);
+}
+
+#[test]
+fn delta_map_merge_sums_overlapping_deltas() {
+	let volume = field_id(&["Config", "volume"]);
+	let level = field_id(&["Config", "level"]);
+	let mut map = DeltaMap::from(HashMap::from([(volume.clone(), 1.0), (level.clone(), 2.0)]));
+	map.merge(&DeltaMap::from(HashMap::from([(volume.clone(), 0.5)])));
+	assert_eq!(map.0, HashMap::from([(volume.clone(), 1.5), (level.clone(), 2.0)]));
+	map.scale(2.0);
+	assert_eq!(map.0, HashMap::from([(volume, 3.0), (level, 4.0)]));
+	let mut value = config();
+	map.apply(&mut value).unwrap();
+	assert_eq!(value, Config { volume: 4.0, level: 5, ..config() });
+}
+
+#[test]
+fn delta_map_matches_the_free_functions() {
+	let left = Config { volume: 3.0, level: 4, ..config() };
+	let map = DeltaMap::caculate(&left, &config()).unwrap();
+	assert_eq!(HashMap::from(map.clone()), caculate_delta(&left, &config()).unwrap());
+	let mut value = config();
+	map.apply(&mut value).unwrap();
+	assert_eq!(value, left);
+}
+
+#[test]
+fn tolerance_leaves_out_float_noise() {
+	let left = Floats { a: 0.1 + 0.2, b: 1.0 };
+	let right = Floats { a: 0.3, b: 0.5 };
+	assert_eq!(caculate_delta(&left, &right).unwrap().len(), 2);
+	let delta = caculate_delta_tol(&left, &right, 1e-9).unwrap();
+	assert_eq!(delta, HashMap::from([(field_id(&["Floats", "b"]), 0.5)]));
+	assert!(caculate_delta_tol(&left, &right, 0.5).unwrap().is_empty());
+}
+
+#[test]
+fn tolerance_does_not_affect_ints() {
+	let left = Config { level: 2, volume: 1.0 + 1e-12, ..config() };
+	let delta = caculate_delta_tol(&left, &config(), 10.0).unwrap();
+	assert_eq!(delta, HashMap::from([(field_id(&["Config", "level"]), 1.0)]));
+}
+
+#[test]
+fn has_changes_on_identical_and_differing_values() {
+	assert!(!has_changes(&config(), &config()).unwrap());
+	assert!(has_changes(&Config { muted: true, ..config() }, &config()).unwrap());
+	let left = Inventory { items: [("a".to_string(), 1)].into(), note: None, weight: None };
+	let right = Inventory { items: Default::default(), note: None, weight: None };
+	assert!(has_changes(&left, &right).unwrap());
+}
+
+#[test]
+fn changed_count_matches_the_delta_map() {
+	assert_eq!(changed_count(&config(), &config()).unwrap(), 0);
+	let left = Config { volume: 3.0, level: 2, muted: true, ..config() };
+	assert_eq!(changed_count(&left, &config()).unwrap(), 3);
+	let left = Inventory { items: [("a".to_string(), 1), ("b".to_string(), 2)].into(), note: None, weight: None };
+	let right = Inventory { items: [("b".to_string(), 1), ("c".to_string(), 3)].into(), note: None, weight: None };
+	assert_eq!(changed_count(&left, &right).unwrap(), caculate_delta_value(&left, &right).unwrap().len());
+}
@@ -0,0 +1,135 @@
+#[cfg(feature = "std")]
+mod common;
+
+use nablo_data::*;
+
+fn nested_somes(count: usize) -> Vec<u8> {
+	let mut bytes = [0, 0, 0, 0, 9].repeat(count);
+	bytes.extend([0, 0, 0, 0, 8]);
+	bytes
+}
+
+/// drops `data` one node at a time, dropping a tree this deep recursively would overflow the stack
+fn drop_flat(data: ParsedData) {
+	let mut stack = vec![data];
+	while let Some(mut data) = stack.pop() {
+		if let DataEnum::Node(inner) = &mut data.data {
+			stack.append(inner);
+		}
+	}
+}
+
+#[test]
+fn deep_data_is_a_clean_error() {
+	let mut deep = ParsedData::from(1);
+	for _ in 0..10_000 {
+		deep = ParsedData::node(vec![deep, ParsedData::from(2)]);
+	}
+	assert!(matches!(from_data::<serde::de::IgnoredAny>(&mut deep), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	#[cfg(feature = "std")]
+	assert!(matches!(interpolate(&deep, &deep, 0.5), Err(Error::DepthLimitExceeded(_))));
+	#[cfg(feature = "std")]
+	assert!(matches!(flatten(&deep), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	drop_flat(deep);
+}
+
+#[test]
+fn depth_limit_is_configurable() {
+	let mut data = ParsedData::from(1);
+	for _ in 0..100 {
+		data = ParsedData::node(vec![data, ParsedData::from(2)]);
+	}
+	assert!(from_data::<serde::de::IgnoredAny>(&mut data.clone()).is_ok());
+	assert!(matches!(from_data_with_max_depth::<serde::de::IgnoredAny>(&mut data, 50), Err(Error::DepthLimitExceeded(50))));
+}
+
+#[test]
+fn deep_bytes_are_a_clean_error() {
+	assert!(matches!(from_bytes(&nested_somes(10_000)), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	assert!(matches!(from_bytes(&nested_somes(MAX_DEPTH)), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	assert_eq!(from_bytes(&nested_somes(MAX_DEPTH - 1)).unwrap().depth(), MAX_DEPTH);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn deep_flat_ids_are_a_clean_error() {
+	let deep = std::collections::HashMap::from([("----a".repeat(200_000), DataEnum::Int(1, 0..=1))]);
+	assert!(matches!(unflatten(&deep), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	let limit = std::collections::HashMap::from([("----a".repeat(MAX_DEPTH), DataEnum::Int(1, 0..=1))]);
+	assert_eq!(unflatten(&limit).unwrap().depth(), MAX_DEPTH);
+}
+
+/// every link nests two levels, the link and its `next` sequence
+#[cfg(feature = "std")]
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+struct Chain {
+	x: f64,
+	next: Vec<Chain>,
+}
+
+#[cfg(feature = "std")]
+fn chain(length: usize, x: f64) -> Chain {
+	let mut chain = Chain { x, next: vec![] };
+	for _ in 1..length {
+		chain = Chain { x: 0.0, next: vec![chain] };
+	}
+	chain
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn depth_limit_is_configurable_for_deltas_and_flat_maps() {
+	let (left, mut right) = (chain(100, 1.0), chain(100, 0.5));
+	assert!(matches!(caculate_delta(&left, &right), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	let delta = caculate_delta_with_max_depth(&left, &right, 1000).unwrap();
+	assert_eq!(delta.values().copied().collect::<Vec<f64>>(), vec![0.5]);
+	assert!(matches!(apply_delta(&mut right, &delta), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	apply_delta_with_max_depth(&mut right, &delta, 1000).unwrap();
+	assert_eq!(right, left);
+	let data = to_data(&left).unwrap();
+	assert_eq!(interpolate_with_max_depth(&data, &data, 0.5, 1000).unwrap(), data);
+	assert!(matches!(flatten(&data), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	let flat = flatten_with_max_depth(&data, 1000).unwrap();
+	assert!(matches!(unflatten(&flat), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	let mut data = unflatten_with_max_depth(&flat, 1000).unwrap();
+	assert_eq!(from_data_with_max_depth::<Chain>(&mut data, 1000).unwrap(), left);
+}
+
+#[cfg(feature = "std")]
+struct DeepTarget {
+	chain: Chain,
+	map: std::collections::HashMap<String, nablo_shape::prelude::Animation>,
+	max_depth: usize,
+}
+
+#[cfg(feature = "std")]
+impl CanBeAnimated<'_, Chain> for DeepTarget {
+	fn get_animation_map(&mut self) -> &mut std::collections::HashMap<String, nablo_shape::prelude::Animation> {
+		&mut self.map
+	}
+
+	fn get_animate_target(&mut self) -> &mut Chain {
+		&mut self.chain
+	}
+
+	fn get_max_depth(&self) -> usize {
+		self.max_depth
+	}
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn depth_limit_is_configurable_for_animations() {
+	let map = AnimationMapBuilder::new("Chain").animation(common::linear(0.0, 2.0, 2)).build();
+	let mut target = DeepTarget { chain: chain(100, 0.0), map, max_depth: MAX_DEPTH };
+	assert!(matches!(target.caculate(&time::Duration::seconds(1)), Err(Error::DepthLimitExceeded(MAX_DEPTH))));
+	target.max_depth = 1000;
+	target.caculate(&time::Duration::seconds(1)).unwrap();
+	let mut link = &target.chain;
+	while let Some(next) = link.next.first() {
+		assert_eq!(link.x, 1.0);
+		link = next;
+	}
+	assert_eq!(link.x, 1.0);
+}
+
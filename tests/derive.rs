@@ -0,0 +1,61 @@
+#![cfg(feature = "derive")]
+
+mod common;
+
+use nablo_data::*;
+use nablo_shape::prelude::Animation;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use time::Duration;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Style {
+	alpha: f32,
+	width: u8,
+}
+
+#[derive(CanBeAnimated)]
+struct Button {
+	label: String,
+	#[animate]
+	style: Style,
+	#[animations]
+	animations: HashMap<String, Animation>,
+}
+
+#[derive(CanBeAnimated)]
+struct Generic<T: Serialize + for<'a> Deserialize<'a>> {
+	#[animate]
+	target: T,
+	#[animations]
+	animations: HashMap<String, Animation>,
+}
+
+#[test]
+fn derived_impl_animates_the_target() {
+	let mut button = Button {
+		label: "ok".to_string(),
+		style: Style { alpha: 0.0, width: 0 },
+		animations: AnimationMapBuilder::new("Style").animation(common::linear(0.0, 10.0, 1)).build(),
+	};
+	button.caculate(&Duration::milliseconds(500)).unwrap();
+	assert_eq!(button.style, Style { alpha: 5.0, width: 5 });
+	assert_eq!(button.label, "ok");
+}
+
+#[test]
+fn derived_impl_on_generic_struct() {
+	let mut generic = Generic {
+		target: Style { alpha: 0.0, width: 0 },
+		animations: AnimationMapBuilder::new("Style").field("width").animation(common::linear(0.0, 10.0, 1)).build(),
+	};
+	generic.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(generic.target, Style { alpha: 0.0, width: 10 });
+}
+
+#[test]
+fn missing_or_repeated_markers_are_compile_errors() {
+	let cases = trybuild::TestCases::new();
+	cases.compile_fail("tests/ui/*.rs");
+}
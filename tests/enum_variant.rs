@@ -0,0 +1,21 @@
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum E {
+	N(i32),
+}
+
+#[test]
+fn newtype_variant_roundtrip() {
+	let mut data = to_data(&E::N(3)).unwrap();
+	assert_eq!(from_data::<E>(&mut data).unwrap(), E::N(3));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn empty_newtype_variant_from_json_is_an_error() {
+	let mut data = from_json(&serde_json::json!({"N": []}));
+	assert!(from_data::<E>(&mut data).is_err());
+}
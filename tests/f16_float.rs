@@ -0,0 +1,36 @@
+#![cfg(feature = "half")]
+
+use half::f16;
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Style {
+	#[serde(with = "nablo_data::f16_float")]
+	alpha: f16,
+}
+
+#[test]
+fn f16_is_stored_as_float() {
+	let data = to_data(&Style { alpha: f16::from_f32(0.5) }).unwrap();
+	assert_eq!(data.get("alpha").unwrap().data, DataEnum::Float(0.5));
+	assert_eq!(ParsedData::from(f16::from_f32(0.25)).data, DataEnum::Float(0.25));
+}
+
+#[test]
+fn f16_roundtrips() {
+	for value in [0.0, 0.5, -1.25, 65504.0, f16::EPSILON.to_f32(), f16::MIN_POSITIVE_SUBNORMAL.to_f32()] {
+		let style = Style { alpha: f16::from_f32(value) };
+		let mut data = to_data(&style).unwrap();
+		assert_eq!(from_data::<Style>(&mut data).unwrap(), style);
+	}
+}
+
+#[test]
+fn narrowing_rounds_to_the_nearest_f16() {
+	let mut data = ParsedData::named("Style", ParsedData::node(vec![ParsedData::named("alpha", 0.1f32)]));
+	assert_eq!(from_data::<Style>(&mut data).unwrap().alpha, f16::from_f32(0.1));
+	let mut data = ParsedData::named("Style", ParsedData::node(vec![ParsedData::named("alpha", 1e6f32)]));
+	assert_eq!(from_data::<Style>(&mut data).unwrap().alpha, f16::INFINITY);
+}
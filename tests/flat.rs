@@ -0,0 +1,119 @@
+#![cfg(feature = "std")]
+
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Kind {
+	Unit,
+	Newtype(i32),
+	Struct { x: f32 },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Inner {
+	a: f32,
+	b: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct State {
+	level: u8,
+	inner: Inner,
+	list: Vec<i32>,
+	map: BTreeMap<String, Inner>,
+	unit: Kind,
+	fields: Kind,
+	empty: Vec<u8>,
+	hint: Option<bool>,
+	newtype: Kind,
+}
+
+fn state() -> State {
+	State {
+		level: 1,
+		inner: Inner { a: 2.0, b: "x".to_string() },
+		list: (0..12).collect(),
+		map: BTreeMap::from([("k".to_string(), Inner { a: 3.0, b: "y".to_string() })]),
+		unit: Kind::Unit,
+		fields: Kind::Struct { x: 1.0 },
+		empty: vec![],
+		hint: None,
+		newtype: Kind::Newtype(4),
+	}
+}
+
+#[test]
+fn nested_struct_roundtrips() {
+	let flat = flatten(&to_data(&state()).unwrap()).unwrap();
+	assert_eq!(flat.get(&field_id(&["State", "inner", "a"])), Some(&DataEnum::Float(2.0)));
+	assert_eq!(flat.get(&field_id(&["State", "unit"])), Some(&DataEnum::Enum("Unit".to_string(), vec![])));
+	let mut data = unflatten(&flat).unwrap();
+	assert_eq!(flatten(&data).unwrap(), flat);
+	assert_eq!(from_data::<State>(&mut data).unwrap(), state());
+}
+
+#[test]
+fn missing_nodes_are_created() {
+	let flat = HashMap::from([(field_id(&["S", "x", "y", "z"]), DataEnum::Bool(true))]);
+	assert_eq!(unflatten(&flat).unwrap().get_path("x.y.z").unwrap().data, DataEnum::Bool(true));
+}
+
+#[test]
+fn collisions_are_errors() {
+	let flat = HashMap::from([
+		(field_id(&["S", "a"]), DataEnum::Bool(true)),
+		(field_id(&["S", "a", "b"]), DataEnum::Bool(true)),
+	]);
+	assert!(matches!(unflatten(&flat), Err(Error::At(_, _))));
+	let flat = HashMap::from([
+		(field_id(&["S", "a"]), DataEnum::Bool(true)),
+		(field_id(&["T", "a"]), DataEnum::Bool(true)),
+	]);
+	assert!(matches!(unflatten(&flat), Err(Error::UnknownField(_))));
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Leaf {
+	b: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Keys {
+	m: BTreeMap<String, f32>,
+	n: BTreeMap<String, Leaf>,
+}
+
+fn keys(value: f32) -> Keys {
+	Keys {
+		m: BTreeMap::from([("a----b".to_string(), value), ("x-".to_string(), value), ("50%".to_string(), value)]),
+		n: BTreeMap::from([("a".to_string(), Leaf { b: value })]),
+	}
+}
+
+#[test]
+fn names_with_the_separator_do_not_collide() {
+	assert_eq!(field_id(&["Foo", "a", "b"]), "----Foo----a----b");
+	assert_eq!(field_id(&["Foo", "my-field"]), "----Foo----my-field");
+	assert_ne!(field_id(&["Keys", "m", "a----b"]), field_id(&["Keys", "m", "a", "b"]));
+	assert_ne!(field_id(&["x-", "y"]), field_id(&["x", "-y"]));
+	let data = to_data(&keys(1.0)).unwrap();
+	let ids: Vec<String> = data.leaves().map(|(id, _)| id).collect();
+	let unique: std::collections::HashSet<&String> = ids.iter().collect();
+	assert_eq!(unique.len(), ids.len());
+	assert!(ids.contains(&field_id(&["Keys", "m", "a----b", ""])));
+	assert_eq!(unflatten(&flatten(&data).unwrap()).unwrap(), data);
+}
+
+#[test]
+fn deltas_keep_names_with_the_separator_apart() {
+	let delta = caculate_delta(&keys(1.0), &keys(0.0)).unwrap();
+	assert_eq!(delta.len(), 4);
+	assert_eq!(delta[&field_id(&["Keys", "m", "a----b", ""])], 1.0);
+	let mut value = keys(0.0);
+	apply_delta(&mut value, &delta).unwrap();
+	assert_eq!(value, keys(1.0));
+}
@@ -0,0 +1,60 @@
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Wide {
+	signed: i128,
+	unsigned: u128,
+}
+
+#[test]
+fn i128_extremes_roundtrip() {
+	for signed in [i128::MIN, i128::MAX] {
+		let value = Wide { signed, unsigned: i128::MAX as u128 };
+		let mut data = to_data(&value).unwrap();
+		assert_eq!(from_data::<Wide>(&mut data).unwrap(), value);
+	}
+}
+
+#[test]
+fn u128_above_i128_max_is_an_error() {
+	let value = Wide { signed: 0, unsigned: u128::MAX };
+	assert!(to_data(&value).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn delta_of_i128_extremes_does_not_overflow() {
+	let left = Wide { signed: i128::MAX, unsigned: 0 };
+	let right = Wide { signed: i128::MIN, unsigned: 0 };
+	let delta = caculate_delta(&left, &right).unwrap();
+	assert_eq!(delta.values().copied().collect::<Vec<_>>(), [i128::MAX as f64 - i128::MIN as f64]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn delta_of_close_large_values_is_exact() {
+	let left = Wide { signed: i128::MAX, unsigned: 0 };
+	let right = Wide { signed: i128::MAX - 1, unsigned: 0 };
+	let delta = caculate_delta(&left, &right).unwrap();
+	assert_eq!(delta.values().copied().collect::<Vec<_>>(), [1.0]);
+}
+
+#[test]
+fn u128_conversion_matches_to_data() {
+	assert_eq!(ParsedData::try_from(i128::MAX as u128).unwrap(), to_data(&(i128::MAX as u128)).unwrap());
+	assert!(ParsedData::try_from(u128::MAX).is_err());
+}
+
+#[test]
+fn i128_and_u128_read_narrower_ints() {
+	let mut data = ParsedData::named("Wide", ParsedData::node(vec![ParsedData::named("signed", -3i32), ParsedData::named("unsigned", 7u8)]));
+	assert_eq!(from_data::<Wide>(&mut data).unwrap(), Wide { signed: -3, unsigned: 7 });
+}
+
+#[test]
+fn negative_u128_is_an_error() {
+	let mut data = ParsedData::named("Wide", ParsedData::node(vec![ParsedData::named("signed", 0), ParsedData::named("unsigned", -1)]));
+	assert!(from_data::<Wide>(&mut data).is_err());
+}
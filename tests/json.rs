@@ -0,0 +1,85 @@
+#![cfg(feature = "json")]
+
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum Kind {
+	Unit,
+	Newtype(i32),
+	Tuple(i32, bool),
+	Struct { q: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Inner {
+	z: f64,
+	y: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Outer {
+	x: i32,
+	inner: Inner,
+	kinds: Vec<Kind>,
+	map: BTreeMap<String, i32>,
+}
+
+fn roundtrip<T: Serialize + for<'a> Deserialize<'a>>(value: &T) -> T {
+	from_data(&mut from_json(&to_json(&to_data(value).unwrap()))).unwrap()
+}
+
+#[test]
+fn nested_struct_roundtrips() {
+	let value = Outer {
+		x: 5,
+		inner: Inner { z: 1.5, y: vec![1, 2] },
+		kinds: vec![Kind::Unit, Kind::Newtype(4), Kind::Tuple(1, true), Kind::Struct { q: "w".to_string() }],
+		map: BTreeMap::from([("k".to_string(), 3), ("l".to_string(), 4)]),
+	};
+	assert_eq!(roundtrip(&value), value);
+}
+
+#[test]
+fn json_layout() {
+	let value = Outer {
+		x: 5,
+		inner: Inner { z: 1.5, y: vec![] },
+		kinds: vec![Kind::Unit, Kind::Newtype(4)],
+		map: BTreeMap::from([("k".to_string(), 3)]),
+	};
+	assert_eq!(to_json(&to_data(&value).unwrap()), json!({
+		"x": 5,
+		"inner": { "z": 1.5, "y": [] },
+		"kinds": ["Unit", { "Newtype": 4 }],
+		"map": { "k": 3 },
+	}));
+}
+
+#[test]
+fn bytes_are_base64() {
+	assert_eq!(to_json(&ParsedData::from(&b"abc"[..])), json!("YWJj"));
+}
+
+#[test]
+fn from_json_layout() {
+	let data = from_json(&json!({ "a": [1, null], "b": "c" }));
+	assert_eq!(data.get_path("a.0").unwrap().data, DataEnum::Int(1, i64::MIN as i128..=i64::MAX as i128));
+	assert_eq!(data.get_path("a.1").unwrap().data, DataEnum::None);
+	assert_eq!(data.get("b").unwrap().data, DataEnum::String("c".to_string()));
+}
+
+#[test]
+fn deserialized_ints_keep_their_range_valid() {
+	let valid = DataEnum::Int(5, 0..=255);
+	assert_eq!(serde_json::from_str::<DataEnum>(&serde_json::to_string(&valid).unwrap()).unwrap(), valid);
+	let outside = serde_json::to_string(&DataEnum::Int(300, 0..=255)).unwrap();
+	assert!(serde_json::from_str::<DataEnum>(&outside).is_err());
+	let reversed = serde_json::to_string(&DataEnum::Int(5, std::ops::RangeInclusive::new(255, 0))).unwrap();
+	assert!(serde_json::from_str::<DataEnum>(&reversed).is_err());
+	let nested = ParsedData::named("S", ParsedData::node(vec![ParsedData { data: DataEnum::Int(-1, 0..=1), name: "x".to_string() }]));
+	assert!(serde_json::from_str::<ParsedData>(&serde_json::to_string(&nested).unwrap()).is_err());
+}
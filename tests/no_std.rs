@@ -0,0 +1,39 @@
+//! built with `--no-default-features`, where the library is `no_std` and only uses `alloc`
+
+#![cfg(not(feature = "std"))]
+
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Widget {
+	x: f32,
+	count: u8,
+	label: String,
+	children: Vec<Option<i32>>,
+	tags: BTreeMap<String, bool>,
+}
+
+fn widget() -> Widget {
+	Widget {
+		x: 1.5,
+		count: 3,
+		label: "a".into(),
+		children: vec![None, Some(1)],
+		tags: BTreeMap::from([("k".into(), true)]),
+	}
+}
+
+#[test]
+fn data_roundtrips_without_std() {
+	let mut data = to_data(&widget()).unwrap();
+	assert_eq!(from_data::<Widget>(&mut data).unwrap(), widget());
+}
+
+#[test]
+fn bytes_roundtrip_without_std() {
+	let data = to_data(&widget()).unwrap();
+	assert_eq!(from_bytes(&to_bytes(&data).unwrap()).unwrap(), data);
+}
@@ -0,0 +1,537 @@
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+struct Inner {
+	c: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Outer {
+	a: f32,
+	b: Inner,
+	v: Vec<Inner>,
+	m: BTreeMap<String, i32>,
+}
+
+fn outer() -> Outer {
+	Outer {
+		a: 1.5,
+		b: Inner { c: 2 },
+		v: vec![Inner { c: 3 }, Inner { c: 4 }],
+		m: BTreeMap::from([("k".to_string(), 5)]),
+	}
+}
+
+#[test]
+fn get_finds_struct_fields() {
+	let data = to_data(&outer()).unwrap();
+	assert_eq!(data.get("a").unwrap().data, DataEnum::Float(1.5));
+	assert_eq!(data.get("b").unwrap().get("c").unwrap().data, DataEnum::Int(2, i32::MIN as i128..=i32::MAX as i128));
+}
+
+#[test]
+fn get_steps_into_map_values() {
+	let data = to_data(&outer()).unwrap();
+	assert_eq!(data.get("m").unwrap().get("k").unwrap().data, DataEnum::Int(5, i32::MIN as i128..=i32::MAX as i128));
+}
+
+#[test]
+fn get_missing_is_none() {
+	let data = to_data(&outer()).unwrap();
+	assert!(data.get("missing").is_none());
+	assert!(data.get("a").unwrap().get("a").is_none());
+	assert!(data.get("m").unwrap().get("missing").is_none());
+}
+
+#[test]
+fn get_mut_changes_the_field() {
+	let mut data = to_data(&outer()).unwrap();
+	data.get_mut("m").unwrap().get_mut("k").unwrap().data = DataEnum::Int(6, i32::MIN as i128..=i32::MAX as i128);
+	data.get_mut("b").unwrap().get_mut("c").unwrap().data = DataEnum::Int(7, i32::MIN as i128..=i32::MAX as i128);
+	let value = from_data::<Outer>(&mut data).unwrap();
+	assert_eq!((value.m["k"], value.b.c), (6, 7));
+	assert!(to_data(&outer()).unwrap().get_mut("missing").is_none());
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Deep {
+	layers: Vec<Outer>,
+}
+
+#[test]
+fn get_path_walks_nested_structs_and_sequences() {
+	let data = to_data(&Deep { layers: vec![outer(), outer()] }).unwrap();
+	assert_eq!(data.get_path("layers.1.v.0.c").unwrap().data, DataEnum::Int(3, i32::MIN as i128..=i32::MAX as i128));
+	assert_eq!(data.get_path("layers.0.m.k").unwrap().data, DataEnum::Int(5, i32::MIN as i128..=i32::MAX as i128));
+	assert_eq!(data.get_path("layers.0.a").unwrap().data, DataEnum::Float(1.5));
+	assert!(data.get_path("layers.2").is_none());
+	assert!(data.get_path("layers.0.missing").is_none());
+}
+
+#[test]
+fn get_path_rejects_empty_segments() {
+	let data = to_data(&outer()).unwrap();
+	for path in ["", ".", "b.", ".b", "b..c"] {
+		assert!(data.get_path(path).is_none(), "{}", path);
+	}
+	assert!(data.get_path("b.c").is_some());
+}
+
+#[test]
+fn leaves_lists_every_leaf_with_its_id() {
+	let data = to_data(&outer()).unwrap();
+	let ids: Vec<String> = data.leaves().map(|(id, _)| id).collect();
+	assert_eq!(ids, [
+		field_id(&["Outer", "a"]),
+		field_id(&["Outer", "b", "c"]),
+		field_id(&["Outer", "v", "0", "c"]),
+		field_id(&["Outer", "v", "1", "c"]),
+		field_id(&["Outer", "m", "k", ""]),
+	]);
+	let values: Vec<&DataEnum> = data.leaves().map(|(_, data)| data).collect();
+	assert_eq!(values[0], &DataEnum::Float(1.5));
+	assert_eq!(values[4].as_f64(), Some(5.0));
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename = "Inner")]
+struct InnerPatch {
+	c: i32,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename = "Outer")]
+struct OuterPatch {
+	b: InnerPatch,
+	m: BTreeMap<String, i32>,
+}
+
+#[test]
+fn merge_overlays_a_partial_struct() {
+	let mut base = to_data(&outer()).unwrap();
+	let patch = to_data(&OuterPatch { b: InnerPatch { c: 9 }, m: BTreeMap::from([("k".to_string(), 8), ("new".to_string(), 7)]) }).unwrap();
+	merge(&mut base, &patch);
+	let merged = from_data::<Outer>(&mut base).unwrap();
+	assert_eq!(merged, Outer {
+		b: Inner { c: 9 },
+		m: BTreeMap::from([("k".to_string(), 8), ("new".to_string(), 7)]),
+		..outer()
+	});
+}
+
+#[test]
+fn merge_overwrites_different_variants() {
+	let mut base = to_data(&Some(1.0f32)).unwrap();
+	merge(&mut base, &to_data(&None::<f32>).unwrap());
+	assert_eq!(from_data::<Option<f32>>(&mut base).unwrap(), None);
+	let mut base = to_data(&Some(1.0f32)).unwrap();
+	merge(&mut base, &ParsedData::from("text"));
+	assert_eq!(base.data, DataEnum::String("text".to_string()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn reversed_int_range_clamps_like_a_swapped_one() {
+	let reversed = std::ops::RangeInclusive::new(10, 0);
+	let int = |value| ParsedData { data: DataEnum::Int(value, reversed.clone()), name: String::new() };
+	let blended = |a, b, t| interpolate(&int(a), &int(b), t).unwrap().data.as_f64();
+	assert_eq!(blended(0, 8, 0.5), Some(4.0));
+	assert_eq!(blended(0, 40, 1.0), Some(10.0));
+	assert_eq!(blended(0, -40, 1.0), Some(0.0));
+}
+
+#[derive(Serialize, Debug)]
+enum Shape {
+	Circle { radius: f32 },
+}
+
+#[derive(Serialize, Debug)]
+struct Scene {
+	title: String,
+	visible: bool,
+	shape: Shape,
+	tags: BTreeMap<String, u8>,
+	hint: Option<i8>,
+}
+
+#[test]
+fn pretty_string_snapshot() {
+	let scene = Scene {
+		title: "main".to_string(),
+		visible: true,
+		shape: Shape::Circle { radius: 1.5 },
+		tags: BTreeMap::from([("a".to_string(), 1)]),
+		hint: Some(-2),
+	};
+	assert_eq!(to_pretty_string(&to_data(&scene).unwrap()), "\
+Scene: node
+  title: string \"main\"
+  visible: bool true
+  shape: enum Circle
+    radius: float 1.5
+  tags: node
+    a: map
+      key: string \"a\"
+      value: int 1 (0..=255)
+  hint: int -2 (-128..=127)
+");
+}
+
+#[test]
+fn as_f64_of_numbers_and_bools() {
+	assert_eq!(ParsedData::from(3u8).data.as_f64(), Some(3.0));
+	assert_eq!(ParsedData::from(-1.5).data.as_f64(), Some(-1.5));
+	assert_eq!(DataEnum::Bool(true).as_f64(), Some(1.0));
+	assert_eq!(DataEnum::Bool(false).as_f64(), Some(0.0));
+	assert_eq!(DataEnum::Int(i128::MAX, 0..=i128::MAX).as_f64(), Some(i128::MAX as f64));
+}
+
+#[test]
+fn as_f64_of_others_is_none() {
+	assert_eq!(ParsedData::from("1").data.as_f64(), None);
+	assert_eq!(DataEnum::None.as_f64(), None);
+	assert_eq!(DataEnum::Some(Box::new(ParsedData::from(1))).as_f64(), None);
+	assert_eq!(ParsedData::node(vec![ParsedData::from(1)]).data.as_f64(), None);
+}
+
+#[test]
+fn set_path_edits_nested_leaves() {
+	let mut data = to_data(&Deep { layers: vec![outer()] }).unwrap();
+	data.set_path("layers.0.v.1.c", DataEnum::Int(40, i32::MIN as i128..=i32::MAX as i128)).unwrap();
+	data.set_path("layers.0.m.k", DataEnum::Int(50, i32::MIN as i128..=i32::MAX as i128)).unwrap();
+	let value = from_data::<Deep>(&mut data).unwrap();
+	assert_eq!(value.layers[0].v[1].c, 40);
+	assert_eq!(value.layers[0].m["k"], 50);
+}
+
+#[test]
+fn set_path_errors() {
+	let mut data = to_data(&outer()).unwrap();
+	assert!(matches!(data.set_path("missing", DataEnum::None), Err(Error::UnknownField(_))));
+	assert!(matches!(data.set_path("v.5.c", DataEnum::None), Err(Error::UnknownField(_))));
+	assert!(matches!(data.set_path("b..c", DataEnum::None), Err(Error::UnknownField(_))));
+	assert!(matches!(data.set_path("b", DataEnum::None), Err(Error::UnexpectedType(_, "node"))));
+	assert_eq!(data, to_data(&outer()).unwrap());
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+struct Sparse {
+	a: Option<i32>,
+	b: Option<String>,
+	c: Option<Inner>,
+	d: BTreeMap<String, Option<i32>>,
+	e: Inner,
+}
+
+#[test]
+fn prune_none_drops_none_fields_and_entries() {
+	let sparse = Sparse {
+		a: Some(1),
+		d: BTreeMap::from([("none".to_string(), None), ("some".to_string(), Some(2))]),
+		..Default::default()
+	};
+	let mut data = to_data(&sparse).unwrap();
+	data.prune_none();
+	let names: Vec<&str> = match &data.data {
+		DataEnum::Node(inner) => inner.iter().map(|data| data.name.as_str()).collect(),
+		other => panic!("{:?}", other),
+	};
+	assert_eq!(names, ["a", "d", "e"]);
+	assert!(data.get("d").unwrap().get("none").is_none());
+	assert!(data.get("d").unwrap().get("some").is_some());
+	// missing fields deserialize to `None` again, but the pruned map entry is gone
+	let mut expected = sparse.clone();
+	expected.d.remove("none");
+	assert_eq!(from_data::<Sparse>(&mut data).unwrap(), expected);
+}
+
+#[test]
+fn prune_none_keeps_emptied_nodes() {
+	let mut data = to_data(&BTreeMap::from([("a", None::<i32>)])).unwrap();
+	data.prune_none();
+	assert_eq!(data.data, DataEnum::Node(vec![]));
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Built {
+	name: String,
+	inner: Inner,
+	scores: BTreeMap<String, u8>,
+}
+
+#[test]
+fn hand_built_trees_deserialize() {
+	let mut data = ParsedData::named("Built", ParsedData::node(vec![
+		ParsedData::named("name", "manual"),
+		ParsedData::named("inner", ParsedData::named("Inner", ParsedData::node(vec![ParsedData::named("c", 7)]))),
+		ParsedData::named("scores", ParsedData::node(vec![ParsedData::map("a", 1u8), ParsedData::map("b", 2u8)])),
+	]));
+	let value: Built = from_data(&mut data).unwrap();
+	assert_eq!(value, Built {
+		name: "manual".to_string(),
+		inner: Inner { c: 7 },
+		scores: BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)]),
+	});
+}
+
+#[test]
+fn constructors_match_to_data() {
+	let built = ParsedData::named("Inner", ParsedData::node(vec![ParsedData::named("c", ParsedData::int(3i32))]));
+	assert_eq!(built, to_data(&Inner { c: 3 }).unwrap());
+	let map = ParsedData::node(vec![ParsedData::map("k", ParsedData::int(1u8))]);
+	assert_eq!(map.data, to_data(&BTreeMap::from([("k".to_string(), 1u8)])).unwrap().data);
+}
+
+#[test]
+fn leaf_count_and_depth_of_known_trees() {
+	let data = to_data(&outer()).unwrap();
+	assert_eq!(data.leaf_count(), 5);
+	assert_eq!(data.depth(), 4);
+	assert_eq!(data.leaf_count(), data.leaves().count());
+	let data = ParsedData { data: DataEnum::Some(Box::new(ParsedData { data: DataEnum::Some(Box::new(ParsedData::from(1))), name: String::new() })), name: String::new() };
+	assert_eq!((data.leaf_count(), data.depth()), (1, 3));
+	let data = ParsedData::node(vec![]);
+	assert_eq!((data.leaf_count(), data.depth()), (0, 1));
+	assert_eq!(ParsedData::from(1).depth(), 1);
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct RenamedInner {
+	count: i32,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename = "Outer")]
+struct Migrated {
+	alpha: f32,
+	b: RenamedInner,
+	v: Vec<Inner>,
+	m: BTreeMap<String, i32>,
+}
+
+#[test]
+fn renamed_fields_deserialize_into_the_new_type() {
+	let mut data = to_data(&outer()).unwrap();
+	data.rename("a", "alpha").unwrap();
+	data.rename("b.c", "count").unwrap();
+	data.rename("m.k", "key").unwrap();
+	assert_eq!(from_data::<Migrated>(&mut data).unwrap(), Migrated {
+		alpha: 1.5,
+		b: RenamedInner { count: 2 },
+		v: vec![Inner { c: 3 }, Inner { c: 4 }],
+		m: BTreeMap::from([("key".to_string(), 5)]),
+	});
+}
+
+#[test]
+fn rename_errors() {
+	let mut data = to_data(&outer()).unwrap();
+	assert!(matches!(data.rename("missing", "x"), Err(Error::UnknownField(_))));
+	assert!(matches!(data.rename("b.missing", "x"), Err(Error::UnknownField(_))));
+	assert!(matches!(data.rename("a", "b"), Err(Error::DuplicateKey(_))));
+	let mut data = to_data(&BTreeMap::from([(1u8, 2u8)])).unwrap();
+	assert!(matches!(data.rename("1", "one"), Err(Error::UnexpectedType(..))));
+}
+
+#[test]
+fn display_of_scalars() {
+	assert_eq!(DataEnum::Int(-3, 0..=10).to_string(), "-3");
+	assert_eq!(DataEnum::Float(1.5).to_string(), "1.5");
+	assert_eq!(DataEnum::Float(2.0).to_string(), "2.0");
+	assert_eq!(DataEnum::String("plain text".to_string()).to_string(), "plain text");
+	assert_eq!(DataEnum::Bool(true).to_string(), "true");
+	assert_eq!(DataEnum::Bool(false).to_string(), "false");
+	assert_eq!(DataEnum::None.to_string(), "None");
+	assert_eq!(DataEnum::Some(Box::new(ParsedData::from(4))).to_string(), "4");
+	assert_eq!(DataEnum::Data(vec![1, 2, 3]).to_string(), "Data(3 bytes)");
+}
+
+#[test]
+fn display_of_containers() {
+	assert_eq!(to_data(&outer()).unwrap().data.to_string(), "Node(4 fields)");
+	assert_eq!(ParsedData::map("k", 1).data.to_string(), "Map(k)");
+	assert_eq!(DataEnum::Enum("Unit".to_string(), vec![]).to_string(), "Unit");
+	assert_eq!(DataEnum::Enum("Pair".to_string(), vec![ParsedData::from(1), ParsedData::from(2)]).to_string(), "Pair(2 fields)");
+}
+
+#[test]
+fn take_child_removes_it() {
+	let mut data = to_data(&outer()).unwrap();
+	let inner = data.take_child("b").unwrap();
+	assert_eq!(inner, ParsedData::named("b", to_data(&Inner { c: 2 }).unwrap()));
+	assert!(data.get("b").is_none());
+	assert!(data.get("a").is_some());
+	assert!(data.take_child("b").is_none());
+	let mut map = data.take_child("m").unwrap();
+	let value = map.take_child("k").unwrap();
+	assert!(matches!(value.data, DataEnum::Int(5, _)));
+	assert!(map.get("k").is_none());
+	assert!(value.clone().take_child("x").is_none());
+}
+
+#[derive(Serialize)]
+struct Maps {
+	m: std::collections::HashMap<String, Vec<u8>>,
+	n: std::collections::HashMap<(u8, u8), u8>,
+}
+
+#[test]
+fn eq_unordered_ignores_the_order_of_maps() {
+	let a = to_data(&Maps {
+		m: [("a".to_string(), vec![1, 2]), ("b".to_string(), vec![3])].into(),
+		n: [((1, 2), 3), ((4, 5), 6)].into(),
+	}).unwrap();
+	let mut b = a.clone();
+	if let DataEnum::Node(inner) = &mut b.data {
+		for child in inner.iter_mut() {
+			if let DataEnum::Node(entries) = &mut child.data {
+				entries.reverse();
+			}
+		}
+	}
+	assert_ne!(a, b);
+	assert!(a.eq_unordered(&b));
+	assert!(b.eq_unordered(&a));
+	let mut changed = b.clone();
+	changed.set_path("m.a.0", DataEnum::Int(9, 0..=255)).unwrap();
+	assert!(!a.eq_unordered(&changed));
+}
+
+#[test]
+fn eq_unordered_keeps_sequences_ordered_by_index() {
+	let a = to_data(&vec![1u8, 2]).unwrap();
+	let mut swapped = a.clone();
+	if let DataEnum::Node(inner) = &mut swapped.data {
+		inner.swap(0, 1);
+	}
+	assert!(a.eq_unordered(&swapped));
+	assert!(!a.eq_unordered(&to_data(&vec![2u8, 1]).unwrap()));
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Pos {
+	x: f32,
+	y: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Item {
+	name: String,
+	pos: Pos,
+}
+
+#[derive(Serialize)]
+struct World {
+	items: Vec<Item>,
+	tags: BTreeMap<String, Item>,
+}
+
+#[test]
+fn from_data_at_reads_a_subtree() {
+	let item = Item { name: "a".to_string(), pos: Pos { x: 1.0, y: 2.0 } };
+	let world = World {
+		items: vec![item.clone(), Item { name: "b".to_string(), pos: Pos { x: 3.0, y: 4.0 } }],
+		tags: BTreeMap::from([("k".to_string(), item.clone())]),
+	};
+	let mut data = to_data(&world).unwrap();
+	assert_eq!(from_data_at::<Pos>(&mut data, "items.1.pos").unwrap(), Pos { x: 3.0, y: 4.0 });
+	assert_eq!(from_data_at::<Item>(&mut data, "tags.k").unwrap(), item);
+	assert_eq!(from_data_at::<String>(&mut data, "items.0.name").unwrap(), "a");
+	assert_eq!(data.get_path("items.0.pos.x").unwrap().data, DataEnum::Float(1.0));
+	assert!(matches!(from_data_at::<Pos>(&mut data, "items.5"), Err(Error::UnknownField(path)) if path == "items.5"));
+}
+
+#[test]
+fn int_infers_the_range_of_its_type() {
+	assert_eq!(ParsedData::int::<u8>(5).data, DataEnum::Int(5, 0..=255));
+	assert_eq!(ParsedData::int(-3i16).data, DataEnum::Int(-3, -32768..=32767));
+	assert_eq!(ParsedData::int(7u32).data, DataEnum::Int(7, 0..=u32::MAX as i128));
+	assert_eq!(ParsedData::int(1i64).data, DataEnum::Int(1, i64::MIN as i128..=i64::MAX as i128));
+	assert_eq!(ParsedData::int(2u64).data, DataEnum::Int(2, 0..=u64::MAX as i128));
+	assert_eq!(ParsedData::int(0i128).data, DataEnum::Int(0, i128::MIN..=i128::MAX));
+	assert_eq!(<u8 as IntType>::range(), 0..=255);
+}
+
+#[test]
+fn int_matches_to_data() {
+	assert_eq!(ParsedData::int(9u16), to_data(&9u16).unwrap());
+	assert_eq!(ParsedData::int(-9i8), to_data(&-9i8).unwrap());
+}
+
+#[test]
+fn from_data_with_rewrites_leaves_before_reading() {
+	let mut data = to_data(&Pos { x: 1.5, y: -2.0 }).unwrap();
+	let mut ids = vec!();
+	let pos: Pos = from_data_with(&mut data, |id, data| {
+		ids.push(id.to_string());
+		if let DataEnum::Float(value) = data {
+			*value *= 2.0;
+		}
+	}).unwrap();
+	assert_eq!(pos, Pos { x: 3.0, y: -4.0 });
+	assert_eq!(ids, vec!(field_id(&["Pos", "x"]), field_id(&["Pos", "y"])));
+}
+
+#[test]
+fn from_data_with_reaches_nested_leaves() {
+	let mut data = to_data(&outer()).unwrap();
+	let value: Outer = from_data_with(&mut data, |_, data| match data {
+		DataEnum::Float(value) => *value *= 2.0,
+		DataEnum::Int(value, _) => *value += 1,
+		_ => {},
+	}).unwrap();
+	assert_eq!(value.a, 3.0);
+	assert_eq!((value.b.c, value.v[0].c, value.v[1].c, value.m["k"]), (3, 4, 5, 6));
+}
+
+#[test]
+fn walk_mut_redacts_string_leaves() {
+	let world = World {
+		items: vec![Item { name: "a".to_string(), pos: Pos { x: 1.0, y: 2.0 } }],
+		tags: BTreeMap::from([("k".to_string(), Item { name: "b".to_string(), pos: Pos { x: 3.0, y: 4.0 } })]),
+	};
+	let mut data = to_data(&world).unwrap();
+	let mut redacted = vec!();
+	data.walk_mut(&mut |id, data| if let DataEnum::String(text) = data {
+		redacted.push(id.to_string());
+		*text = "***".to_string();
+	});
+	assert_eq!(redacted, vec!(field_id(&["World", "items", "0", "name"]), field_id(&["World", "tags", "k", "Item", "name"])));
+	assert_eq!(from_data_at::<String>(&mut data, "items.0.name").unwrap(), "***");
+	assert_eq!(from_data_at::<Item>(&mut data, "tags.k").unwrap(), Item { name: "***".to_string(), pos: Pos { x: 3.0, y: 4.0 } });
+}
+
+#[test]
+fn walk_mut_visits_parents_before_children() {
+	let mut data = to_data(&Inner { c: 1 }).unwrap();
+	let mut ids = vec!();
+	data.walk_mut(&mut |id, _| ids.push(id.to_string()));
+	assert_eq!(ids, vec!(field_id(&["Inner"]), field_id(&["Inner", "c"])));
+}
+
+#[test]
+fn get_pointer_matches_get_path() {
+	let data = to_data(&Deep { layers: vec![outer(), outer()] }).unwrap();
+	for (pointer, path) in [("/layers/1/v/0/c", "layers.1.v.0.c"), ("/layers/0/m/k", "layers.0.m.k"), ("/layers/0/a", "layers.0.a")] {
+		assert_eq!(data.get_pointer(pointer), data.get_path(path), "{}", pointer);
+	}
+	assert_eq!(data.get_pointer(""), Some(&data));
+	assert!(data.get_pointer("/layers/2").is_none());
+	assert!(data.get_pointer("layers/0").is_none());
+}
+
+#[test]
+fn get_pointer_unescapes_slash_and_tilde() {
+	let data = to_data(&BTreeMap::from([
+		("a/b".to_string(), 1u8),
+		("c~d".to_string(), 2),
+		("~1".to_string(), 3),
+	])).unwrap();
+	assert_eq!(data.get_pointer("/a~1b").unwrap().data, DataEnum::Int(1, 0..=255));
+	assert_eq!(data.get_pointer("/c~0d").unwrap().data, DataEnum::Int(2, 0..=255));
+	assert_eq!(data.get_pointer("/~01").unwrap().data, DataEnum::Int(3, 0..=255));
+	assert!(data.get_pointer("/a/b").is_none());
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "profile")]
+
+use nablo_data::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+enum Kind {
+	Unit,
+	Newtype(u8),
+}
+
+#[derive(Serialize)]
+struct Counted {
+	a: u8,
+	b: Vec<u16>,
+	c: Option<bool>,
+	d: Option<Option<u8>>,
+	m: BTreeMap<String, u8>,
+	e: Kind,
+	f: Kind,
+}
+
+#[test]
+fn profile_counts_every_value() {
+	let value = Counted {
+		a: 1,
+		b: vec![2, 3],
+		c: Some(true),
+		d: Some(None),
+		m: BTreeMap::from([("k".to_string(), 4)]),
+		e: Kind::Unit,
+		f: Kind::Newtype(5),
+	};
+	let (data, profile) = to_data_profiled(&value).unwrap();
+	assert_eq!(data, to_data(&value).unwrap());
+	// nodes: Counted, b, the outer Some of d, m, the entry of m, e and f
+	// leaves: a, b.0, b.1, c, the None of d, the key and value of m and the 5 of f
+	assert_eq!(profile, Profile { nodes: 7, leaves: 8 });
+}
+
+#[test]
+fn profile_of_a_single_value() {
+	assert_eq!(to_data_profiled(&1u8).unwrap().1, Profile { nodes: 0, leaves: 1 });
+}
@@ -0,0 +1,173 @@
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Ints {
+	a: u8,
+	b: i16,
+	c: u64,
+	d: i64,
+}
+
+#[test]
+fn int_ranges_follow_the_rust_type() {
+	let data = to_data(&Ints { a: 1, b: 2, c: 3, d: 4 }).unwrap();
+	assert_eq!(data.get("a").unwrap().data, DataEnum::Int(1, 0..=255));
+	assert_eq!(data.get("b").unwrap().data, DataEnum::Int(2, -32768..=32767));
+	assert_eq!(data.get("c").unwrap().data, DataEnum::Int(3, 0..=u64::MAX as i128));
+	assert_eq!(data.get("d").unwrap().data, DataEnum::Int(4, i64::MIN as i128..=i64::MAX as i128));
+}
+
+#[test]
+fn int_ranges_survive_round_trips() {
+	let data = to_data(&Ints { a: 1, b: -2, c: 3, d: -4 }).unwrap();
+	let value: Ints = from_data(&mut data.clone()).unwrap();
+	assert_eq!(to_data(&value).unwrap(), data);
+}
+
+fn capacity(data: &ParsedData) -> usize {
+	match &data.data {
+		DataEnum::Node(inner) => inner.capacity(),
+		other => panic!("{:?}", other),
+	}
+}
+
+#[test]
+fn known_lengths_are_reserved() {
+	assert_eq!(capacity(&to_data(&vec![1u8; 5]).unwrap()), 5);
+	assert_eq!(capacity(&to_data(&(1, 2.0, "three")).unwrap()), 3);
+	assert_eq!(capacity(&to_data(&Ints { a: 1, b: 2, c: 3, d: 4 }).unwrap()), 4);
+}
+
+/// a sequence that does not tell serde its length
+struct Unsized(Vec<u8>);
+
+impl Serialize for Unsized {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(self.0.iter().filter(|_| true))
+	}
+}
+
+#[test]
+fn unknown_lengths_still_serialize() {
+	let mut data = to_data(&Unsized(vec![1, 2, 3])).unwrap();
+	assert_eq!(from_data::<Vec<u8>>(&mut data).unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn unknown_lengths_reserve_the_given_capacity() {
+	let mut data = to_data_with_capacity(&Unsized(vec![1, 2, 3]), 64).unwrap();
+	assert_eq!(capacity(&data), 64);
+	assert_eq!(from_data::<Vec<u8>>(&mut data).unwrap(), [1, 2, 3]);
+	assert_eq!(capacity(&to_data_with_capacity(&vec![1u8; 5], 64).unwrap()), 5);
+}
+
+/// a map that writes the same key twice
+struct Duplicated;
+
+impl Serialize for Duplicated {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeMap;
+		let mut map = serializer.serialize_map(Some(3))?;
+		map.serialize_entry("a", &1)?;
+		map.serialize_entry("b", &2)?;
+		map.serialize_entry("a", &3)?;
+		map.end()
+	}
+}
+
+#[test]
+fn strict_rejects_duplicate_keys() {
+	assert!(matches!(to_data_strict(&Duplicated), Err(Error::DuplicateKey(key)) if key == "a"));
+	let map = std::collections::BTreeMap::from([("a", 1), ("b", 2)]);
+	assert_eq!(to_data_strict(&map).unwrap(), to_data(&map).unwrap());
+}
+
+#[test]
+fn lenient_keeps_duplicate_keys() {
+	let mut data = to_data(&Duplicated).unwrap();
+	match &data.data {
+		DataEnum::Node(inner) => assert_eq!(inner.len(), 3),
+		other => panic!("{:?}", other),
+	}
+	// deserializing into a map keeps the last one, like inserting them in order
+	assert_eq!(from_data::<std::collections::BTreeMap<String, i32>>(&mut data).unwrap(), [("a".to_string(), 3), ("b".to_string(), 2)].into());
+}
+
+#[test]
+fn ints_without_ranges_are_smaller() {
+	let value = Ints { a: 1, b: 2, c: 3, d: 4 };
+	let ranged = to_data(&value).unwrap();
+	let plain = to_data_without_ranges(&value).unwrap();
+	assert_eq!(plain.get("a").unwrap().data, DataEnum::Int(1, i128::MIN..=i128::MAX));
+	assert!(to_bytes(&plain).unwrap().len() < to_bytes(&ranged).unwrap().len());
+	assert_eq!(from_bytes(&to_bytes(&plain).unwrap()).unwrap(), plain);
+	assert_eq!(from_data::<Ints>(&mut plain.clone()).unwrap(), value);
+}
+
+/// a value that can not be serialized
+struct Failing;
+
+impl Serialize for Failing {
+	fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+		Err(serde::ser::Error::custom("broken"))
+	}
+}
+
+#[derive(Serialize)]
+struct Holder {
+	fine: u8,
+	items: Vec<Failing>,
+}
+
+#[derive(Serialize)]
+struct Root {
+	holder: Holder,
+}
+
+#[test]
+fn serialize_errors_carry_the_field_path() {
+	let error = to_data(&Root { holder: Holder { fine: 1, items: vec![Failing] } }).unwrap_err();
+	match &error {
+		Error::At(path, inner) => {
+			assert_eq!(path, &field_id(&["Root", "holder", "items", "0"]));
+			assert!(matches!(&**inner, Error::SerdeError(message) if message == "broken"));
+		},
+		other => panic!("{:?}", other),
+	}
+	assert!(error.to_string().contains("----Root----holder----items----0"), "{}", error);
+}
+
+/// a map that writes a value before its key
+struct ValueFirst;
+
+impl Serialize for ValueFirst {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeMap;
+		let mut map = serializer.serialize_map(None)?;
+		map.serialize_value(&1u8)?;
+		map.end()
+	}
+}
+
+/// a map that writes two values for one key
+struct TwoValues;
+
+impl Serialize for TwoValues {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeMap;
+		let mut map = serializer.serialize_map(None)?;
+		map.serialize_key("a")?;
+		map.serialize_value(&1u8)?;
+		map.serialize_value(&2u8)?;
+		map.end()
+	}
+}
+
+#[test]
+fn map_values_without_keys_are_errors() {
+	assert!(matches!(to_data(&ValueFirst), Err(Error::Syntax)));
+	assert!(matches!(to_data(&vec![ValueFirst]), Err(Error::At(_, inner)) if matches!(*inner, Error::Syntax)));
+	assert!(matches!(to_data(&TwoValues), Err(Error::Syntax)));
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use common::Animated;
+use nablo_data::*;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use time::Duration;
+use time::OffsetDateTime;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Fade {
+	#[serde(with = "nablo_data::time_float::duration")]
+	delay: Duration,
+	#[serde(with = "nablo_data::time_float::offset_date_time")]
+	started: OffsetDateTime,
+}
+
+fn fade() -> Fade {
+	Fade {
+		delay: Duration::milliseconds(1500),
+		started: OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+	}
+}
+
+#[test]
+fn time_fields_are_single_floats() {
+	let data = to_data(&fade()).unwrap();
+	assert_eq!(data.get("delay").unwrap().data, DataEnum::Float(1.5));
+	assert_eq!(data.get("started").unwrap().data, DataEnum::Float(1_700_000_000.0));
+}
+
+#[test]
+fn time_fields_roundtrip() {
+	let mut data = to_data(&fade()).unwrap();
+	assert_eq!(from_data::<Fade>(&mut data).unwrap(), fade());
+}
+
+#[test]
+fn invalid_seconds_are_errors() {
+	for seconds in [f64::NAN, f64::INFINITY] {
+		let mut data = to_data(&fade()).unwrap();
+		data.set_path("delay", DataEnum::Float(seconds)).unwrap();
+		assert!(from_data::<Fade>(&mut data).is_err());
+		let mut data = to_data(&fade()).unwrap();
+		data.set_path("started", DataEnum::Float(seconds)).unwrap();
+		assert!(from_data::<Fade>(&mut data).is_err());
+	}
+}
+
+#[test]
+fn duration_can_be_animated() {
+	let map = HashMap::from([(field_id(&["Fade", "delay"]), common::linear(0.0, 4.0, 2))]);
+	let mut animated = Animated::new(fade(), map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target, Fade { delay: Duration::seconds(2), ..fade() });
+}
+
+#[serde_with::serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Timer {
+	#[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+	delay: std::time::Duration,
+	#[serde_as(as = "serde_with::DurationSeconds<u64>")]
+	whole: std::time::Duration,
+}
+
+#[test]
+fn serde_with_durations_are_single_leaves() {
+	let timer = Timer { delay: std::time::Duration::from_millis(1500), whole: std::time::Duration::from_secs(3) };
+	let data = to_data(&timer).unwrap();
+	assert_eq!(data.get("delay").unwrap().data, DataEnum::Float(1.5));
+	assert!(matches!(data.get("whole").unwrap().data, DataEnum::Int(3, _)));
+	let map = AnimationMapBuilder::new("Timer")
+		.field("delay").animation(common::linear(0.0, 4.0, 2))
+		.field("whole").animation(common::linear(0.0, 4.0, 2))
+		.build();
+	let mut animated = Animated::new(timer, map);
+	animated.caculate(&Duration::seconds(1)).unwrap();
+	assert_eq!(animated.target, Timer { delay: std::time::Duration::from_secs(2), whole: std::time::Duration::from_secs(2) });
+}
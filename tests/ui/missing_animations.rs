@@ -0,0 +1,7 @@
+#[derive(nablo_data::CanBeAnimated)]
+struct Button {
+	#[animate]
+	style: (f32, f32),
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use nablo_shape::prelude::Animation;
+use std::collections::HashMap;
+
+#[derive(nablo_data::CanBeAnimated)]
+struct Button {
+	#[animate]
+	style: (f32, f32),
+	#[animate]
+	hover: (f32, f32),
+	#[animations]
+	animations: HashMap<String, Animation>,
+}
+
+fn main() {}